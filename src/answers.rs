@@ -0,0 +1,178 @@
+//! known answers keyed by day and part, read from a small subset of toml:
+//!
+//! ```toml
+//! [day01]
+//! part1 = 53651
+//! part2 = "some text"
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::solution::Answer;
+
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Status {
+    Pass,
+    Fail,
+    Unknown,
+}
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Pass => write!(f, "PASS"),
+            Status::Fail => write!(f, "FAIL"),
+            Status::Unknown => write!(f, "UNKNOWN"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Default)]
+pub struct Answers {
+    answers: HashMap<(u32, u32), Answer>,
+}
+impl Answers {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut answers = HashMap::new();
+        let mut day = None;
+        for (idx, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = |reason: &str| format!("line {}: {} [{}]", idx + 1, reason, line);
+
+            if let Some(table) = line.strip_prefix('[') {
+                let name = table
+                    .strip_suffix(']')
+                    .ok_or_else(|| invalid("unclosed table"))?;
+                day = Some(
+                    name.strip_prefix("day")
+                        .and_then(|n| n.parse::<u32>().ok())
+                        .ok_or_else(|| invalid("expected [dayNN]"))?,
+                );
+                continue;
+            }
+
+            let day = day.ok_or_else(|| invalid("answer outside of a [dayNN] table"))?;
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| invalid("expected `partN = <answer>`"))?;
+            let part = match key.trim() {
+                "part1" => 1,
+                "part2" => 2,
+                _ => return Err(invalid("expected part1 or part2")),
+            };
+            let answer = parse_value(value.trim()).ok_or_else(|| invalid("invalid answer"))?;
+
+            if answers.insert((day, part), answer).is_some() {
+                return Err(invalid("duplicated answer"));
+            }
+        }
+
+        Ok(Answers { answers })
+    }
+
+    /// reads `path`, or `answers.toml` in the current directory or the crate root.
+    /// a missing default file means no answers are known.
+    pub fn load(path: Option<&Path>) -> Result<Self, String> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => {
+                let local = PathBuf::from(ANSWERS_FILE);
+                let root = Path::new(env!("CARGO_MANIFEST_DIR")).join(ANSWERS_FILE);
+                match [local, root].into_iter().find(|p| p.exists()) {
+                    Some(path) => path,
+                    None => return Ok(Answers::default()),
+                }
+            }
+        };
+
+        let input = std::fs::read_to_string(&path)
+            .map_err(|e| format!("can't read [{}]: {}", path.display(), e))?;
+        Answers::parse(&input).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn get(&self, day: u32, part: u32) -> Option<&Answer> {
+        self.answers.get(&(day, part))
+    }
+}
+
+fn parse_value(value: &str) -> Option<Answer> {
+    if let Some(s) = value.strip_prefix('"') {
+        return Some(Answer::Str(s.strip_suffix('"')?.to_string()));
+    }
+    value.parse::<i64>().ok().map(Answer::Int)
+}
+
+/// answers are compared as printed, so `1` and `"1"` are the same
+pub fn check(expected: Option<&Answer>, answer: &Answer) -> Status {
+    match expected {
+        Some(expected) if expected.to_string() == answer.to_string() => Status::Pass,
+        Some(_) => Status::Fail,
+        None => Status::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let answers = Answers::parse(
+            r#"# comment
+[day01]
+part1 = 53651
+part2 = "abc"
+
+[day12]
+part2 = -3
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(answers.get(1, 1), Some(&Answer::Int(53651)));
+        assert_eq!(answers.get(1, 2), Some(&Answer::Str("abc".to_string())));
+        assert_eq!(answers.get(12, 1), None);
+        assert_eq!(answers.get(12, 2), Some(&Answer::Int(-3)));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert!(Answers::parse("part1 = 1").is_err());
+        assert!(Answers::parse("[day01\npart1 = 1").is_err());
+        assert!(Answers::parse("[year01]\npart1 = 1").is_err());
+        assert!(Answers::parse("[day01]\npart3 = 1").is_err());
+        assert!(Answers::parse("[day01]\npart1 = one").is_err());
+        assert_eq!(
+            Answers::parse("[day01]\npart1 = 1\npart1 = 2"),
+            Err("line 3: duplicated answer [part1 = 2]".to_string())
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        let answer = Answer::Int(7);
+        assert_eq!(check(Some(&Answer::Int(7)), &answer), Status::Pass);
+        assert_eq!(
+            check(Some(&Answer::Str("7".to_string())), &answer),
+            Status::Pass
+        );
+        assert_eq!(check(Some(&Answer::Int(8)), &answer), Status::Fail);
+        assert_eq!(check(None, &answer), Status::Unknown);
+    }
+
+    #[test]
+    fn test_answers_file() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(ANSWERS_FILE);
+        let answers = Answers::load(Some(&path)).unwrap();
+        for day in 1..=5 {
+            assert!(answers.get(day, 1).is_some());
+            assert!(answers.get(day, 2).is_some());
+        }
+    }
+}
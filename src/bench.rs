@@ -0,0 +1,305 @@
+//! times the parse and both parts of each day over many iterations, using only std.
+//! results can be saved as a baseline, and later runs compared against it to spot regressions.
+
+use std::time::Duration;
+
+use crate::error::Error;
+use crate::registry::Day;
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Stage {
+    Parse,
+    Part1,
+    Part2,
+}
+impl Stage {
+    pub const ALL: [Stage; 3] = [Stage::Parse, Stage::Part1, Stage::Part2];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Stage::Parse => "parse",
+            Stage::Part1 => "part1",
+            Stage::Part2 => "part2",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Stage::ALL.into_iter().find(|s| s.name() == name)
+    }
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+}
+impl Stats {
+    /// `samples` must not be empty
+    pub fn new(samples: &mut [Duration]) -> Self {
+        samples.sort();
+        let mid = samples.len() / 2;
+        let median = if samples.len().is_multiple_of(2) {
+            (samples[mid - 1] + samples[mid]) / 2
+        } else {
+            samples[mid]
+        };
+
+        Stats {
+            min: samples[0],
+            median,
+            mean: samples.iter().sum::<Duration>() / samples.len() as u32,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Measurement {
+    pub day: u32,
+    pub stage: Stage,
+    pub stats: Stats,
+}
+
+/// runs `day` `warmup` times without measuring, then `iterations` times measuring each stage
+pub fn bench(
+    day: &Day,
+    input: &str,
+    warmup: usize,
+    iterations: usize,
+) -> Result<Vec<Measurement>, Error> {
+    let run = || (day.run)(input, &[1, 2]).map_err(|e| e.in_day(day.day));
+    for _ in 0..warmup {
+        run()?;
+    }
+
+    let mut samples = vec![Vec::with_capacity(iterations); Stage::ALL.len()];
+    for _ in 0..iterations {
+        let run = run()?;
+        samples[0].push(run.parse);
+        // a part that is not implemented yet has nothing to measure
+        for p in run.parts.iter().filter(|p| p.answer.is_some()) {
+            samples[p.part as usize].push(p.elapsed);
+        }
+    }
+
+    Ok(Stage::ALL
+        .into_iter()
+        .zip(samples)
+        .filter(|(_, s)| !s.is_empty())
+        .map(|(stage, mut s)| Measurement {
+            day: day.day,
+            stage,
+            stats: Stats::new(&mut s),
+        })
+        .collect())
+}
+
+/// one measurement per line: `<day> <stage> <min_ns> <median_ns> <mean_ns>`
+pub fn format_baseline(measurements: &[Measurement]) -> String {
+    let mut baseline = "# day stage min_ns median_ns mean_ns\n".to_string();
+    for m in measurements {
+        baseline += &format!(
+            "{} {} {} {} {}\n",
+            m.day,
+            m.stage.name(),
+            m.stats.min.as_nanos(),
+            m.stats.median.as_nanos(),
+            m.stats.mean.as_nanos()
+        );
+    }
+    baseline
+}
+
+pub fn parse_baseline(input: &str) -> Result<Vec<Measurement>, String> {
+    let mut measurements = Vec::new();
+    for (idx, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let invalid = || format!("invalid baseline line {}: [{}]", idx + 1, line);
+        let parsed = line.split_whitespace().collect::<Vec<_>>();
+        let [day, stage, min, median, mean] = parsed.as_slice() else {
+            return Err(invalid());
+        };
+        let nanos = |s: &str| {
+            s.parse::<u64>()
+                .map(Duration::from_nanos)
+                .map_err(|_| invalid())
+        };
+
+        measurements.push(Measurement {
+            day: day.parse().map_err(|_| invalid())?,
+            stage: Stage::from_name(stage).ok_or_else(invalid)?,
+            stats: Stats {
+                min: nanos(min)?,
+                median: nanos(median)?,
+                mean: nanos(mean)?,
+            },
+        });
+    }
+    Ok(measurements)
+}
+
+/// change of the median against the baseline in percent, `None` if the stage isn't in it
+pub fn compare(current: &Measurement, baseline: &[Measurement]) -> Option<f64> {
+    let base = baseline
+        .iter()
+        .find(|b| b.day == current.day && b.stage == current.stage)?;
+    let base_ns = base.stats.median.as_nanos().max(1) as f64;
+    Some((current.stats.median.as_nanos() as f64 - base_ns) / base_ns * 100.0)
+}
+
+fn format_duration(d: Duration) -> String {
+    format!("{:.3}ms", d.as_secs_f64() * 1000.0)
+}
+
+/// the report table, and whether any stage is slower than the baseline by more than `threshold` percent
+pub fn format_report(
+    measurements: &[Measurement],
+    baseline: Option<&[Measurement]>,
+    threshold: f64,
+) -> (String, bool) {
+    let mut regressed = false;
+    let mut report = format!(
+        "day  stage  {:>10}  {:>10}  {:>10}",
+        "min", "median", "mean"
+    );
+    if baseline.is_some() {
+        report += &format!("  {:>10}  {:>8}", "baseline", "change");
+    }
+    report += "\n";
+
+    for m in measurements {
+        report += &format!(
+            "{:>3}  {:<5}  {:>10}  {:>10}  {:>10}",
+            m.day,
+            m.stage.name(),
+            format_duration(m.stats.min),
+            format_duration(m.stats.median),
+            format_duration(m.stats.mean)
+        );
+
+        if let Some(baseline) = baseline {
+            let base = baseline
+                .iter()
+                .find(|b| b.day == m.day && b.stage == m.stage);
+            match (base, compare(m, baseline)) {
+                (Some(base), Some(change)) => {
+                    report += &format!(
+                        "  {:>10}  {:>+7.1}%",
+                        format_duration(base.stats.median),
+                        change
+                    );
+                    if change > threshold {
+                        regressed = true;
+                        report += "  REGRESSION";
+                    }
+                }
+                _ => report += &format!("  {:>10}  {:>8}", "-", "new"),
+            }
+        }
+        report += "\n";
+    }
+
+    (report, regressed)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::registry;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    fn measurement(day: u32, stage: Stage, median: u64) -> Measurement {
+        Measurement {
+            day,
+            stage,
+            stats: Stats {
+                min: ms(median / 2),
+                median: ms(median),
+                mean: ms(median),
+            },
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::new(&mut [ms(5), ms(1), ms(3)]);
+        assert_eq!(
+            stats,
+            Stats {
+                min: ms(1),
+                median: ms(3),
+                mean: ms(3),
+            }
+        );
+
+        let stats = Stats::new(&mut [ms(4), ms(1), ms(2), ms(9)]);
+        assert_eq!(stats.median, ms(3));
+        assert_eq!(stats.mean, ms(4));
+    }
+
+    #[test]
+    fn test_bench() {
+        let day = registry::find(1).unwrap();
+        let measurements = bench(day, "two1nine\n1abc2", 1, 3).unwrap();
+        let stages = measurements.iter().map(|m| m.stage).collect::<Vec<_>>();
+        assert_eq!(stages, Stage::ALL);
+        assert!(measurements.iter().all(|m| m.day == 1));
+    }
+
+    #[test]
+    fn test_baseline_round_trip() {
+        let measurements = vec![
+            measurement(1, Stage::Parse, 2),
+            measurement(1, Stage::Part2, 40),
+        ];
+        let saved = format_baseline(&measurements);
+        assert_eq!(parse_baseline(&saved), Ok(measurements));
+
+        assert!(parse_baseline("1 parse 1 2").is_err());
+        assert!(parse_baseline("1 part3 1 2 3").is_err());
+    }
+
+    #[test]
+    fn test_compare() {
+        let baseline = vec![measurement(1, Stage::Part1, 10)];
+        assert_eq!(
+            compare(&measurement(1, Stage::Part1, 15), &baseline),
+            Some(50.0)
+        );
+        assert_eq!(
+            compare(&measurement(1, Stage::Part1, 5), &baseline),
+            Some(-50.0)
+        );
+        assert_eq!(compare(&measurement(2, Stage::Part1, 5), &baseline), None);
+    }
+
+    #[test]
+    fn test_format_report() {
+        let baseline = vec![
+            measurement(1, Stage::Part1, 10),
+            measurement(1, Stage::Part2, 10),
+        ];
+        let current = vec![
+            measurement(1, Stage::Part1, 11),
+            measurement(1, Stage::Part2, 20),
+            measurement(2, Stage::Part1, 20),
+        ];
+
+        let (report, regressed) = format_report(&current, Some(&baseline), 15.0);
+        assert!(regressed);
+        let lines = report.lines().collect::<Vec<_>>();
+        assert!(lines[1].ends_with("+10.0%"));
+        assert!(lines[2].ends_with("+100.0%  REGRESSION"));
+        assert!(lines[3].ends_with("new"));
+
+        let (_, regressed) = format_report(&current, None, 15.0);
+        assert!(!regressed);
+    }
+}
@@ -0,0 +1,193 @@
+use std::process::ExitCode;
+
+use advent_of_code_2023::answers::{Answers, Status};
+use advent_of_code_2023::cli::{
+    self, BenchOptions, Command, FetchOptions, NewOptions, RunOptions, SubmitOptions,
+};
+use advent_of_code_2023::registry::{self, Day};
+use advent_of_code_2023::site::{self, Fetched};
+use advent_of_code_2023::{bench, examples, html, input, runner, scaffold, submit};
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let result = match cli::parse(&args) {
+        Ok(Command::Run(options)) => run(&options).map(|_| ()),
+        Ok(Command::Check(options)) => check(&options),
+        Ok(Command::Bench(options)) => bench(&options),
+        Ok(Command::Fetch(options)) => fetch(&options),
+        Ok(Command::Submit(options)) => submit(&options),
+        Ok(Command::New(options)) => new(&options),
+        Ok(Command::Examples(options)) => save_examples(&options),
+        Ok(Command::Help) => {
+            println!("{}", cli::USAGE);
+            Ok(())
+        }
+        Err(e) => Err(format!("{}\n{}", e, cli::USAGE)),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// every registered day if `days` is empty
+fn select_days(days: &[u32]) -> Result<Vec<&'static Day>, String> {
+    if days.is_empty() {
+        return Ok(registry::DAYS.iter().collect());
+    }
+
+    days.iter()
+        .map(|&n| registry::find(n).ok_or_else(|| format!("day {} is not solved yet", n)))
+        .collect()
+}
+
+fn run(options: &RunOptions) -> Result<Vec<runner::Outcome>, String> {
+    let answers = Answers::load(options.answers.as_deref())?;
+    // known answers are for the puzzle inputs, not for some other input
+    let answers = options.input.is_none().then_some(&answers);
+
+    let mut outcomes = Vec::new();
+    for day in select_days(&options.days)? {
+        let input = input::load(day.day, options.input.as_deref()).map_err(|e| e.to_string())?;
+
+        let parts = options.part.map_or(vec![1, 2], |p| vec![p]);
+        outcomes.extend(runner::run(day, &parts, &input, answers).map_err(|e| e.diagnostic())?);
+    }
+
+    print!("{}", runner::format(&outcomes, options.format));
+    Ok(outcomes)
+}
+
+fn check(options: &RunOptions) -> Result<(), String> {
+    let outcomes = run(options)?;
+    let failed = outcomes
+        .iter()
+        .filter(|o| o.status != Status::Pass)
+        .map(|o| format!("day {} part {} ({})", o.day, o.part, o.status))
+        .collect::<Vec<_>>();
+
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("not passed: {}", failed.join(", ")))
+    }
+}
+
+fn bench(options: &BenchOptions) -> Result<(), String> {
+    let baseline = match &options.baseline {
+        Some(path) => {
+            let saved = std::fs::read_to_string(path)
+                .map_err(|e| format!("can't read [{}]: {}", path.display(), e))?;
+            Some(bench::parse_baseline(&saved)?)
+        }
+        None => None,
+    };
+
+    let mut measurements = Vec::new();
+    for day in select_days(&options.days)? {
+        let input = input::load(day.day, options.input.as_deref()).map_err(|e| e.to_string())?;
+        measurements.extend(
+            bench::bench(day, &input, options.warmup, options.iterations)
+                .map_err(|e| e.diagnostic())?,
+        );
+    }
+
+    let (report, regressed) =
+        bench::format_report(&measurements, baseline.as_deref(), options.threshold);
+    print!("{}", report);
+
+    if let Some(path) = &options.save_baseline {
+        std::fs::write(path, bench::format_baseline(&measurements))
+            .map_err(|e| format!("can't write [{}]: {}", path.display(), e))?;
+    }
+
+    if regressed {
+        return Err(format!(
+            "some stages are more than {}% slower than the baseline",
+            options.threshold
+        ));
+    }
+    Ok(())
+}
+
+fn site_config(year: Option<u32>) -> Result<site::Config, String> {
+    let mut config = site::Config::from_env()?;
+    if let Some(year) = year {
+        config.year = year;
+    }
+    Ok(config)
+}
+
+fn fetch(options: &FetchOptions) -> Result<(), String> {
+    let config = site_config(options.year)?;
+
+    match site::fetch_input(&config, options.day).map_err(|e| e.to_string())? {
+        Fetched::Cached(path) => println!("already fetched: {}", path.display()),
+        Fetched::Downloaded(path) => println!("fetched: {}", path.display()),
+    }
+    Ok(())
+}
+
+fn submit(options: &SubmitOptions) -> Result<(), String> {
+    let config = site_config(options.year)?;
+    let day = select_days(&[options.day])?[0];
+    let input = input::load(day.day, None).map_err(|e| e.to_string())?;
+    let outcome = runner::run(day, &[options.part], &input, None)
+        .map_err(|e| e.diagnostic())?
+        .pop()
+        .expect("a registered day solves both parts");
+
+    let answer = outcome.answer.ok_or_else(|| {
+        format!(
+            "day {} part {} is not implemented yet",
+            day.day, options.part
+        )
+    })?;
+
+    println!("day {} part {}: {}", day.day, options.part, answer);
+    let reply =
+        submit::submit(&config, day.day, options.part, &answer).map_err(|e| e.to_string())?;
+    println!("{}: {}", reply.verdict, reply.message);
+
+    match reply.verdict {
+        submit::Verdict::Correct => Ok(()),
+        verdict => Err(format!("not accepted: {}", verdict)),
+    }
+}
+
+fn read_page(path: &std::path::Path) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| format!("can't read [{}]: {}", path.display(), e))
+}
+
+fn new(options: &NewOptions) -> Result<(), String> {
+    let sample = match &options.html {
+        Some(path) => html::code_blocks(&read_page(path)?)
+            .into_iter()
+            .next()
+            .ok_or_else(|| format!("no example in [{}]", path.display()))?,
+        None => String::new(),
+    };
+
+    let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+    for path in scaffold::scaffold(root, options.day, &sample)? {
+        println!("wrote {}", path.display());
+    }
+    Ok(())
+}
+
+fn save_examples(options: &NewOptions) -> Result<(), String> {
+    let path = options.html.as_deref().expect("--html is required");
+    let found = examples::extract(&read_page(path)?);
+    if found.is_empty() {
+        return Err(format!("no example in [{}]", path.display()));
+    }
+
+    for path in examples::save(&examples::default_dir(), options.day, &found)? {
+        println!("wrote {}", path.display());
+    }
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use advent_of_code_2023::day01::{self, Language, Mapping, Policy, Vocabulary};
+use advent_of_code_2023::{error, input};
+
+fn main() {
+    let input = &input::load_from_args(1);
+
+    let args = std::env::args().collect::<Vec<_>>();
+    let value = |name| {
+        args.iter()
+            .position(|arg| arg == name)
+            .map(|idx| args.get(idx + 1).map(String::as_str).unwrap_or_default())
+    };
+
+    // the digit words of part 2, e.g. `--language de` or `--vocabulary words.txt`
+    let vocabulary = match (value("--language"), value("--vocabulary")) {
+        (_, Some(path)) => {
+            let words = std::fs::read_to_string(path).unwrap_or_else(|e| {
+                eprintln!("error: can't read [{}]: {}", path, e);
+                std::process::exit(1);
+            });
+            error::or_exit(Vocabulary::parse(&words))
+        }
+        (Some(name), None) => {
+            let language = Language::from_name(name).unwrap_or_else(|| {
+                let names = Language::ALL.map(|l| l.name());
+                eprintln!("unknown language [{}], one of {}", name, names.join(", "));
+                std::process::exit(1);
+            });
+            Vocabulary::language(language)
+        }
+        (None, None) => Vocabulary::language(Language::English),
+    }
+    .with_zero(args.iter().any(|arg| arg == "--zero"))
+    .ignoring_case(args.iter().any(|arg| arg == "--ignore-case"));
+
+    // lines without a value stop the run by default, `--on-missing skip` or `zero` reports them instead
+    let policy = match value("--on-missing") {
+        Some(name) => Policy::from_name(name).unwrap_or_else(|| {
+            let names = Policy::ALL.map(|p| p.name());
+            eprintln!("unknown policy [{}], one of {}", name, names.join(", "));
+            std::process::exit(1);
+        }),
+        None => Policy::default(),
+    };
+
+    let score = |part, mapping: &Mapping| {
+        let report = error::or_exit(day01::score(input, mapping, policy));
+        if !report.missing.is_empty() {
+            let lines = report.missing.iter().map(|n| n.to_string());
+            eprintln!(
+                "part{}: no calibration value on lines {}",
+                part,
+                lines.collect::<Vec<_>>().join(", ")
+            );
+        }
+        println!("part{}: {}", part, report.sum);
+    };
+    let mapping = vocabulary.mapping();
+
+    // every line with the tokens part 2 takes its value from underlined
+    if args.iter().any(|arg| arg == "--explain") {
+        print!(
+            "{}",
+            day01::format_explanations(&day01::explain(input, &mapping))
+        );
+    }
+
+    score(1, &Mapping::new(day01::part1::MAPPING));
+    score(2, &mapping);
+}
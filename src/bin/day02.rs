@@ -0,0 +1,8 @@
+use advent_of_code_2023::{day02, error, input};
+
+fn main() {
+    let input = &input::load_from_args(2);
+
+    println!("part1: {}", error::or_exit(day02::part1::solve(input)));
+    println!("part2: {}", error::or_exit(day02::part2::solve(input)));
+}
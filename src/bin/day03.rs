@@ -0,0 +1,13 @@
+use advent_of_code_2023::{day03, error, input};
+
+fn main() {
+    let input = &input::load_from_args(3);
+    println!(
+        "day03 part1: {}",
+        error::or_exit(day03::part1::solve(input))
+    );
+    println!(
+        "day03 part2: {}",
+        error::or_exit(day03::part2::solve(input))
+    );
+}
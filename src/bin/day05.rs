@@ -0,0 +1,89 @@
+use std::process::ExitCode;
+
+use advent_of_code_2023::day05::{self, Almanac, ColumnLayout};
+use advent_of_code_2023::input;
+
+const USAGE: &str = "usage: day05 [--input <PATH>] [--layout <COLUMNS>] [--print-map [--inverse]]
+             [--seeds-for <LOCATION>] [--convert <FROM> <TO> <VALUE>] [--reverse]";
+
+fn main() -> ExitCode {
+    let input = &input::load_from_args(5);
+    let args = std::env::args().collect::<Vec<_>>();
+
+    match run(input, &args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// the `count` values following the option `name`, `None` if it isn't given
+fn values<'a>(
+    args: &'a [String],
+    name: &str,
+    count: usize,
+) -> Result<Option<&'a [String]>, String> {
+    let Some(idx) = args.iter().position(|arg| arg == name) else {
+        return Ok(None);
+    };
+    args.get(idx + 1..idx + 1 + count)
+        .map(Some)
+        .ok_or_else(|| format!("missing value for [{}]\n{}", name, USAGE))
+}
+
+fn number_arg(s: &str) -> Result<u64, String> {
+    s.parse::<u64>()
+        .map_err(|_| format!("invalid number [{}]\n{}", s, USAGE))
+}
+
+fn run(input: &str, args: &[String]) -> Result<(), String> {
+    // column layout of the map rows, e.g. `--layout src,dest,range`
+    let layout = match values(args, "--layout", 1)? {
+        Some([layout]) => {
+            ColumnLayout::parse(layout).ok_or_else(|| format!("invalid layout [{}]", layout))?
+        }
+        _ => ColumnLayout::default(),
+    };
+
+    let almanac =
+        Almanac::with_layout(input, layout).map_err(|e| e.into_error(input).diagnostic())?;
+
+    // print the whole seed to location chain fused into one map
+    if args.iter().any(|arg| arg == "--print-map") {
+        let maps = almanac.compose_all();
+        if args.iter().any(|arg| arg == "--inverse") {
+            print!("{}", maps.invert());
+        } else {
+            print!("{}", maps);
+        }
+    }
+
+    // which seeds end up in the given location
+    if let Some([location]) = values(args, "--seeds-for", 1)? {
+        let location = number_arg(location)?;
+        println!("seeds: {:?}", almanac.get_seeds(location));
+    }
+
+    // convert a value between any two categories, e.g. `--convert light seed 42`
+    if let Some([from, to, value]) = values(args, "--convert", 3)? {
+        let value = number_arg(value)?;
+        let converted = almanac
+            .convert(from, to, value)
+            .map_err(|e| e.to_string())?;
+        println!("{} {} -> {} {}", from, value, to, converted);
+    }
+
+    let part1 = day05::part1::solve_almanac(&almanac).map_err(|e| e.diagnostic())?;
+    println!("part1: {}", part1);
+    let part2 = day05::part2::solve_almanac(&almanac).map_err(|e| e.diagnostic())?;
+    println!("part2: {}", part2);
+
+    if args.iter().any(|arg| arg == "--reverse") {
+        let reverse = day05::part2::solve_reverse_almanac(&almanac).map_err(|e| e.diagnostic())?;
+        println!("part2 (reverse): {}", reverse);
+    }
+
+    Ok(())
+}
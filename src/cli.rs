@@ -0,0 +1,462 @@
+//! command line arguments of the `aoc` runner
+
+use std::path::PathBuf;
+
+use crate::runner::Format;
+
+pub const USAGE: &str = "usage:
+  aoc run (--day <N> | --all) [--part <1|2>] [--input <PATH|->] [--answers <PATH>]
+          [--format <table|json|csv>]
+  aoc check [--day <N> | --all] [--part <1|2>] [--answers <PATH>] [--format <table|json|csv>]
+  aoc bench (--day <N> [--input <PATH|->] | --all) [--iterations <N>] [--warmup <N>]
+            [--save-baseline <PATH>] [--baseline <PATH> [--threshold <PERCENT>]]
+  aoc fetch --day <N> [--year <YYYY>]
+  aoc submit --day <N> --part <1|2> [--year <YYYY>]
+  aoc new --day <N> [--html <PATH>]
+  aoc examples --day <N> --html <PATH>
+
+inputs are read from inputs/dayNN.txt (or AOC_CACHE_DIR) unless --input is given, `-` reads stdin.
+answers are checked against answers.toml unless --answers is given,
+`check` fails if any answer doesn't match.
+`fetch` downloads an input into that directory unless it's there already, it needs a session token
+in AOC_SESSION or .aoc-session, and AOC_BASE_URL set to an http:// mirror or proxy of the site
+with AOC_ALLOW_HTTP=1 to send the token over it.
+`submit` posts the answer computed from the puzzle input, unless it was wrong before
+or the site asked to wait, every attempt is logged in inputs/attempts.log.
+`new` creates and registers src/dayNN.rs and its binary, taking the sample from the first
+example of a saved puzzle description if --html is given.
+`examples` saves the examples and expected answers of a saved puzzle description
+into examples/, where `cargo test` checks them";
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run(RunOptions),
+    /// same as `Run`, but fails unless every answer passes
+    Check(RunOptions),
+    Bench(BenchOptions),
+    Fetch(FetchOptions),
+    Submit(SubmitOptions),
+    New(NewOptions),
+    /// `html` is always given
+    Examples(NewOptions),
+    Help,
+}
+
+#[derive(Debug, PartialEq, Default)]
+pub struct RunOptions {
+    /// empty means every registered day
+    pub days: Vec<u32>,
+    /// `None` means both parts
+    pub part: Option<u32>,
+    /// `-` for stdin, `None` for the `inputs/` directory
+    pub input: Option<PathBuf>,
+    /// `None` for `answers.toml`
+    pub answers: Option<PathBuf>,
+    pub format: Format,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BenchOptions {
+    /// empty means every registered day
+    pub days: Vec<u32>,
+    pub input: Option<PathBuf>,
+    pub iterations: usize,
+    pub warmup: usize,
+    pub save_baseline: Option<PathBuf>,
+    pub baseline: Option<PathBuf>,
+    /// how much slower than the baseline median counts as a regression, in percent
+    pub threshold: f64,
+}
+impl Default for BenchOptions {
+    fn default() -> Self {
+        BenchOptions {
+            days: Vec::new(),
+            input: None,
+            iterations: 100,
+            warmup: 10,
+            save_baseline: None,
+            baseline: None,
+            threshold: 10.0,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct FetchOptions {
+    pub day: u32,
+    /// `None` for `AOC_YEAR` or the default year
+    pub year: Option<u32>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SubmitOptions {
+    pub day: u32,
+    pub part: u32,
+    /// `None` for `AOC_YEAR` or the default year
+    pub year: Option<u32>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct NewOptions {
+    pub day: u32,
+    /// a saved puzzle description to take the sample from
+    pub html: Option<PathBuf>,
+}
+
+/// `args` excludes the program name
+pub fn parse(args: &[String]) -> Result<Command, String> {
+    let Some((command, rest)) = args.split_first() else {
+        return Ok(Command::Help);
+    };
+
+    match command.as_str() {
+        "run" => parse_run(rest).map(Command::Run),
+        "check" => {
+            // every day unless told otherwise
+            let has_selection = rest.iter().any(|a| a == "--day" || a == "--all");
+            let mut args = rest.to_vec();
+            if !has_selection {
+                args.push("--all".to_string());
+            }
+            parse_run(&args).map(Command::Check)
+        }
+        "bench" => parse_bench(rest).map(Command::Bench),
+        "fetch" => parse_fetch(rest).map(Command::Fetch),
+        "submit" => parse_submit(rest).map(Command::Submit),
+        "new" => parse_new(rest).map(Command::New),
+        "examples" => match parse_new(rest)? {
+            NewOptions { html: None, .. } => Err("--html is required".to_string()),
+            options => Ok(Command::Examples(options)),
+        },
+        "help" | "--help" | "-h" => Ok(Command::Help),
+        _ => Err(format!("unknown command [{}]", command)),
+    }
+}
+
+/// `--day`, `--all` and `--input`, shared by the commands which run days
+#[derive(Default)]
+struct Selection {
+    days: Vec<u32>,
+    all: bool,
+    input: Option<PathBuf>,
+}
+impl Selection {
+    /// returns false if `arg` is not a selection option
+    fn parse_option(
+        &mut self,
+        arg: &str,
+        value: &mut impl FnMut() -> Result<String, String>,
+    ) -> Result<bool, String> {
+        match arg {
+            "--day" => self.days.push(parse_number(&value()?, 1..=25, "day")?),
+            "--input" => self.input = Some(PathBuf::from(value()?)),
+            "--all" => self.all = true,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    fn finish(self) -> Result<(Vec<u32>, Option<PathBuf>), String> {
+        match (self.all, self.days.len()) {
+            (true, 0) | (false, 1) => {}
+            (true, _) => return Err("--all can't be used with --day".to_string()),
+            (false, 0) => return Err("either --day or --all is required".to_string()),
+            (false, _) => return Err("--day can be given only once".to_string()),
+        }
+        if self.input.is_some() && self.all {
+            return Err("--input needs a single --day".to_string());
+        }
+
+        Ok((self.days, self.input))
+    }
+}
+
+fn parse_run(args: &[String]) -> Result<RunOptions, String> {
+    let mut selection = Selection::default();
+    let mut part = None;
+    let mut answers = None;
+    let mut format = Format::default();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = || {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| format!("missing value for [{}]", arg))
+        };
+        if selection.parse_option(arg, &mut value)? {
+            continue;
+        }
+        match arg.as_str() {
+            "--part" => part = Some(parse_number(&value()?, 1..=2, "part")?),
+            "--answers" => answers = Some(PathBuf::from(value()?)),
+            "--format" => {
+                let v = value()?;
+                format = Format::from_name(&v).ok_or_else(|| {
+                    format!("invalid format [{}], expected table, json or csv", v)
+                })?;
+            }
+            _ => return Err(format!("unknown option [{}]", arg)),
+        }
+    }
+
+    let (days, input) = selection.finish()?;
+    Ok(RunOptions {
+        days,
+        part,
+        input,
+        answers,
+        format,
+    })
+}
+
+fn parse_bench(args: &[String]) -> Result<BenchOptions, String> {
+    let mut selection = Selection::default();
+    let mut options = BenchOptions::default();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = || {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| format!("missing value for [{}]", arg))
+        };
+        if selection.parse_option(arg, &mut value)? {
+            continue;
+        }
+        match arg.as_str() {
+            "--iterations" => {
+                options.iterations = parse_number(&value()?, 1..=u32::MAX, "iterations")? as usize
+            }
+            "--warmup" => {
+                options.warmup = parse_number(&value()?, 0..=u32::MAX, "warmup")? as usize
+            }
+            "--save-baseline" => options.save_baseline = Some(PathBuf::from(value()?)),
+            "--baseline" => options.baseline = Some(PathBuf::from(value()?)),
+            "--threshold" => {
+                let v = value()?;
+                options.threshold = match v.parse::<f64>() {
+                    Ok(t) if t >= 0.0 => t,
+                    _ => return Err(format!("invalid threshold [{}]", v)),
+                };
+            }
+            _ => return Err(format!("unknown option [{}]", arg)),
+        }
+    }
+
+    (options.days, options.input) = selection.finish()?;
+    Ok(options)
+}
+
+/// `--day`, `--part` and `--year` of the commands talking to the site
+fn parse_site(args: &[String], with_part: bool) -> Result<(u32, Option<u32>, Option<u32>), String> {
+    let mut day = None;
+    let mut part = None;
+    let mut year = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = || {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| format!("missing value for [{}]", arg))
+        };
+        match arg.as_str() {
+            "--day" if day.is_none() => day = Some(parse_number(&value()?, 1..=25, "day")?),
+            "--part" if with_part => part = Some(parse_number(&value()?, 1..=2, "part")?),
+            "--year" => year = Some(parse_number(&value()?, 2015..=9999, "year")?),
+            _ => return Err(format!("unknown option [{}]", arg)),
+        }
+    }
+
+    let day = day.ok_or("--day is required")?;
+    Ok((day, part, year))
+}
+
+fn parse_fetch(args: &[String]) -> Result<FetchOptions, String> {
+    let (day, _, year) = parse_site(args, false)?;
+    Ok(FetchOptions { day, year })
+}
+
+fn parse_submit(args: &[String]) -> Result<SubmitOptions, String> {
+    let (day, part, year) = parse_site(args, true)?;
+    let part = part.ok_or("--part is required")?;
+    Ok(SubmitOptions { day, part, year })
+}
+
+fn parse_new(args: &[String]) -> Result<NewOptions, String> {
+    let mut day = None;
+    let mut html = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = || {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| format!("missing value for [{}]", arg))
+        };
+        match arg.as_str() {
+            "--day" if day.is_none() => day = Some(parse_number(&value()?, 1..=25, "day")?),
+            "--html" => html = Some(PathBuf::from(value()?)),
+            _ => return Err(format!("unknown option [{}]", arg)),
+        }
+    }
+
+    let day = day.ok_or("--day is required")?;
+    Ok(NewOptions { day, html })
+}
+
+fn parse_number(s: &str, valid: std::ops::RangeInclusive<u32>, name: &str) -> Result<u32, String> {
+    match s.parse::<u32>() {
+        Ok(n) if valid.contains(&n) => Ok(n),
+        _ => Err(format!(
+            "invalid {} [{}], expected {} to {}",
+            name,
+            s,
+            valid.start(),
+            valid.end()
+        )),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_run() {
+        assert_eq!(
+            parse(&args("run --day 3 --part 2 --input foo.txt")),
+            Ok(Command::Run(RunOptions {
+                days: vec![3],
+                part: Some(2),
+                input: Some(PathBuf::from("foo.txt")),
+                answers: None,
+                format: Format::Table,
+            }))
+        );
+        assert_eq!(
+            parse(&args("check --answers a.toml")),
+            Ok(Command::Check(RunOptions {
+                answers: Some(PathBuf::from("a.toml")),
+                ..Default::default()
+            }))
+        );
+        assert_eq!(
+            parse(&args("check --day 2")),
+            Ok(Command::Check(RunOptions {
+                days: vec![2],
+                ..Default::default()
+            }))
+        );
+        assert_eq!(
+            parse(&args("run --all")),
+            Ok(Command::Run(RunOptions::default()))
+        );
+        assert_eq!(
+            parse(&args("check --format json")),
+            Ok(Command::Check(RunOptions {
+                format: Format::Json,
+                ..Default::default()
+            }))
+        );
+        assert_eq!(parse(&args("")), Ok(Command::Help));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert!(parse(&args("walk --day 1")).is_err());
+        assert!(parse(&args("run")).is_err());
+        assert!(parse(&args("run --day")).is_err());
+        assert!(parse(&args("run --day 26")).is_err());
+        assert!(parse(&args("run --day 1 --part 3")).is_err());
+        assert!(parse(&args("run --day 1 --all")).is_err());
+        assert!(parse(&args("run --all --input foo.txt")).is_err());
+        assert!(parse(&args("run --day 1 --verbose")).is_err());
+        assert!(parse(&args("run --day 1 --format xml")).is_err());
+    }
+
+    #[test]
+    fn test_parse_bench() {
+        assert_eq!(
+            parse(&args(
+                "bench --all --iterations 5 --warmup 0 --baseline b.txt --threshold 2.5"
+            )),
+            Ok(Command::Bench(BenchOptions {
+                iterations: 5,
+                warmup: 0,
+                baseline: Some(PathBuf::from("b.txt")),
+                threshold: 2.5,
+                ..Default::default()
+            }))
+        );
+        assert_eq!(
+            parse(&args("bench --day 3 --save-baseline b.txt")),
+            Ok(Command::Bench(BenchOptions {
+                days: vec![3],
+                save_baseline: Some(PathBuf::from("b.txt")),
+                ..Default::default()
+            }))
+        );
+        assert!(parse(&args("bench --day 3 --iterations 0")).is_err());
+        assert!(parse(&args("bench --day 3 --threshold -1")).is_err());
+        assert!(parse(&args("bench --day 3 --part 1")).is_err());
+    }
+
+    #[test]
+    fn test_parse_fetch() {
+        assert_eq!(
+            parse(&args("fetch --day 6 --year 2022")),
+            Ok(Command::Fetch(FetchOptions {
+                day: 6,
+                year: Some(2022),
+            }))
+        );
+        assert!(parse(&args("fetch")).is_err());
+        assert!(parse(&args("fetch --all")).is_err());
+        assert!(parse(&args("fetch --day 1 --day 2")).is_err());
+        assert!(parse(&args("fetch --day 1 --year 1999")).is_err());
+        assert!(parse(&args("fetch --day 1 --part 1")).is_err());
+    }
+
+    #[test]
+    fn test_parse_submit() {
+        assert_eq!(
+            parse(&args("submit --day 5 --part 2")),
+            Ok(Command::Submit(SubmitOptions {
+                day: 5,
+                part: 2,
+                year: None,
+            }))
+        );
+        assert!(parse(&args("submit --day 5")).is_err());
+        assert!(parse(&args("submit --part 1")).is_err());
+        assert!(parse(&args("submit --day 5 --part 3")).is_err());
+    }
+
+    #[test]
+    fn test_parse_new() {
+        assert_eq!(
+            parse(&args("new --day 6 --html day06.html")),
+            Ok(Command::New(NewOptions {
+                day: 6,
+                html: Some(PathBuf::from("day06.html")),
+            }))
+        );
+        assert!(parse(&args("new")).is_err());
+        assert!(parse(&args("new --day 6 --part 1")).is_err());
+
+        assert_eq!(
+            parse(&args("examples --day 6 --html day06.html")),
+            Ok(Command::Examples(NewOptions {
+                day: 6,
+                html: Some(PathBuf::from("day06.html")),
+            }))
+        );
+        assert!(parse(&args("examples --day 6")).is_err());
+    }
+}
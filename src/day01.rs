@@ -1,57 +1,642 @@
-fn main() {
-    let input = include_str!("day01.txt");
+use std::borrow::Cow;
+use std::fmt;
+use std::io::{self, BufRead};
+use std::sync::{mpsc, Mutex, OnceLock};
+use std::thread;
 
-    println!("part1: {}", part1::solve(input));
-    println!("part2: {}", part2::solve(input));
+use crate::error::Error;
+use crate::matcher::{Match, Matcher};
+use crate::solution::{Answer, Solution};
+
+/// a match of a mapping entry in a line
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Token {
+    /// byte offsets in the line
+    pub start: usize,
+    pub end: usize,
+    /// index of the entry in the mapping
+    pub entry: usize,
+    pub value: i32,
+}
+
+/// a mapping with its matcher, built once and used for every line
+#[derive(Debug)]
+pub struct Mapping {
+    patterns: Vec<String>,
+    values: Vec<i32>,
+    matcher: Matcher,
+    ignore_case: bool,
+    /// what a line without any of the patterns lacks
+    expected: &'static str,
+}
+impl Mapping {
+    pub fn new(mapping: &[(&str, i32)]) -> Self {
+        Self::build(mapping, false)
+    }
+
+    /// matches the patterns in any case, as far as `fold_case` goes
+    pub fn ignoring_case(mapping: &[(&str, i32)]) -> Self {
+        Self::build(mapping, true)
+    }
+
+    fn build(mapping: &[(&str, i32)], ignore_case: bool) -> Self {
+        let patterns = mapping
+            .iter()
+            .map(|&(s, _)| if ignore_case { fold_case(s) } else { s.into() })
+            .collect::<Vec<_>>();
+        Mapping {
+            values: mapping.iter().map(|&(_, value)| value).collect(),
+            matcher: Matcher::new(&patterns.iter().map(|s| s.as_bytes()).collect::<Vec<_>>()),
+            patterns: mapping.iter().map(|&(s, _)| s.to_string()).collect(),
+            ignore_case,
+            expected: if patterns
+                .iter()
+                .all(|s| s.len() == 1 && s.as_bytes()[0].is_ascii_digit())
+            {
+                "a digit"
+            } else {
+                "a digit or a digit word"
+            },
+        }
+    }
+
+    /// the pattern and value of an entry
+    pub fn entry(&self, idx: usize) -> (&str, i32) {
+        (&self.patterns[idx], self.values[idx])
+    }
+
+    pub fn solve_line(&self, input: &str) -> Result<i32, Error> {
+        let (first, last) = self.explain_line(input)?;
+        Ok(first.value * 10 + last.value)
+    }
+
+    /// the first and the last token of the line, the ones `solve_line` uses
+    pub fn explain_line(&self, input: &str) -> Result<(Token, Token), Error> {
+        let folded = if self.ignore_case {
+            fold_case(input)
+        } else {
+            input.into()
+        };
+        let (Some(left), Some(right)) = (
+            self.matcher.leftmost(&folded),
+            self.matcher.rightmost(&folded),
+        ) else {
+            return Err(if input.is_empty() {
+                Error::missing(input, input, self.expected)
+            } else {
+                Error::unexpected(input, input, self.expected)
+            });
+        };
+        let token = |m: Match| Token {
+            start: m.start,
+            end: m.end,
+            entry: m.pattern,
+            value: self.values[m.pattern],
+        };
+        Ok((token(left), token(right)))
+    }
+}
+
+/// lowercase, except for the chars whose lowercase takes another number of bytes,
+/// so that offsets in the folded string are offsets in the original one
+pub fn fold_case(s: &str) -> Cow<'_, str> {
+    if !s.chars().any(char::is_uppercase) {
+        return s.into();
+    }
+    s.chars()
+        .map(|c| {
+            let mut lower = c.to_lowercase();
+            match (lower.next(), lower.next()) {
+                (Some(l), None) if l.len_utf8() == c.len_utf8() => l,
+                _ => c,
+            }
+        })
+        .collect::<String>()
+        .into()
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Language {
+    English,
+    German,
+    French,
+    Spanish,
+    /// sino-korean numerals
+    Korean,
 }
+impl Language {
+    pub const ALL: [Language; 5] = [
+        Language::English,
+        Language::German,
+        Language::French,
+        Language::Spanish,
+        Language::Korean,
+    ];
 
-pub fn solve_line_with_mapping(input: &str, mapping: &[(&str, i32)]) -> i32 {
-    // tuple (found_index_in_input, index_in_mapping)
-    let mut left = None;
-    for (idx, &(s, _)) in mapping.iter().enumerate() {
-        let found = input.find(s);
-        match (left, found) {
-            (None, Some(found)) => left = Some((found, idx)),
-            (Some((prev, _)), Some(found)) if found < prev => left = Some((found, idx)),
-            _ => {}
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::German => "de",
+            Language::French => "fr",
+            Language::Spanish => "es",
+            Language::Korean => "ko",
         }
     }
 
-    let mut right = None;
-    for (idx, &(s, _)) in mapping.iter().enumerate() {
-        let found = input.rfind(s);
-        match (right, found) {
-            (None, Some(found)) => right = Some((found, idx)),
-            (Some((prev, _)), Some(found)) if found > prev => right = Some((found, idx)),
-            _ => {}
+    pub fn from_name(name: &str) -> Option<Self> {
+        Language::ALL.into_iter().find(|l| l.name() == name)
+    }
+
+    /// the words for zero to nine
+    pub fn words(&self) -> [&'static str; 10] {
+        match self {
+            Language::English => [
+                "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+            ],
+            Language::German => [
+                "null", "eins", "zwei", "drei", "vier", "fünf", "sechs", "sieben", "acht", "neun",
+            ],
+            Language::French => [
+                "zéro", "un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit", "neuf",
+            ],
+            Language::Spanish => [
+                "cero", "uno", "dos", "tres", "cuatro", "cinco", "seis", "siete", "ocho", "nueve",
+            ],
+            Language::Korean => ["영", "일", "이", "삼", "사", "오", "육", "칠", "팔", "구"],
         }
     }
+}
+
+/// the digit words to look for besides the digits, e.g. for part 2 in another language
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Vocabulary {
+    /// a word and its value, from 0 to 9
+    pub words: Vec<(String, i32)>,
+    /// whether `0` and the words for it count
+    pub zero: bool,
+    pub ignore_case: bool,
+}
+impl Vocabulary {
+    pub fn language(language: Language) -> Self {
+        Vocabulary {
+            words: (0..)
+                .zip(language.words())
+                .map(|(value, word)| (word.to_string(), value))
+                .collect(),
+            zero: false,
+            ignore_case: false,
+        }
+    }
+
+    /// a word and its value per line, e.g. `eins 1`. empty lines and lines starting with `#` are skipped.
+    pub fn parse(input: &str) -> Result<Self, Error> {
+        const EXPECTED: &str = "a value from 0 to 9";
+
+        let mut words = Vec::new();
+        for (idx, line) in input.lines().enumerate() {
+            let at_line = |e: Error| e.at_line(idx + 1);
+            let mut tokens = line.split_whitespace();
+            let Some(word) = tokens.next().filter(|w| !w.starts_with('#')) else {
+                continue;
+            };
+            let value = tokens
+                .next()
+                .ok_or_else(|| at_line(Error::missing(line, word, EXPECTED)))?;
+            let value = match value.parse::<i32>() {
+                Ok(n @ 0..=9) => n,
+                _ => return Err(at_line(Error::unexpected(line, value, EXPECTED))),
+            };
+            if let Some(extra) = tokens.next() {
+                return Err(at_line(Error::unexpected(line, extra, "end of line")));
+            }
+            words.push((word.to_string(), value));
+        }
+
+        Ok(Vocabulary {
+            words,
+            zero: false,
+            ignore_case: false,
+        })
+    }
+
+    pub fn with_zero(self, zero: bool) -> Self {
+        Vocabulary { zero, ..self }
+    }
+
+    pub fn ignoring_case(self, ignore_case: bool) -> Self {
+        Vocabulary {
+            ignore_case,
+            ..self
+        }
+    }
+
+    /// the digits, then the words, for `solve_line_with_mapping`
+    pub fn entries(&self) -> Vec<(&str, i32)> {
+        const DIGITS: [&str; 10] = ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"];
+
+        (0..)
+            .zip(DIGITS)
+            .map(|(value, digit)| (digit, value))
+            .chain(
+                self.words
+                    .iter()
+                    .map(|(word, value)| (word.as_str(), *value)),
+            )
+            .filter(|&(_, value)| self.zero || value != 0)
+            .collect()
+    }
+
+    pub fn mapping(&self) -> Mapping {
+        if self.ignore_case {
+            Mapping::ignoring_case(&self.entries())
+        } else {
+            Mapping::new(&self.entries())
+        }
+    }
+}
+
+/// builds the matcher for a single line, use a `Mapping` for more
+pub fn solve_line_with_mapping(input: &str, mapping: &[(&str, i32)]) -> Result<i32, Error> {
+    Mapping::new(mapping).solve_line(input)
+}
+
+/// what to do with a line without a calibration value
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub enum Policy {
+    /// stop at the first one
+    #[default]
+    Error,
+    /// leave it out
+    Skip,
+    /// count it as a value of 0
+    Zero,
+}
+impl Policy {
+    pub const ALL: [Policy; 3] = [Policy::Error, Policy::Skip, Policy::Zero];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Policy::Error => "error",
+            Policy::Skip => "skip",
+            Policy::Zero => "zero",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Policy::ALL.into_iter().find(|p| p.name() == name)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct Report {
+    pub sum: i64,
+    /// how many lines the sum counts
+    pub counted: usize,
+    /// 1-based numbers of the lines without a calibration value
+    pub missing: Vec<usize>,
+}
+
+/// the sum of the lines, and the lines without a value unless `policy` stops at them.
+/// errors are located in `input`.
+pub fn score(input: &str, mapping: &Mapping, policy: Policy) -> Result<Report, Error> {
+    let mut report = Report::default();
+    for (idx, line) in input.lines().enumerate() {
+        let trimmed = line.trim();
+        match (mapping.solve_line(trimmed), policy) {
+            (Ok(value), _) => {
+                report.sum += i64::from(value);
+                report.counted += 1;
+            }
+            (Err(e), Policy::Error) => return Err(e.within(line, trimmed).at_line(idx + 1)),
+            (Err(_), Policy::Skip) => report.missing.push(idx + 1),
+            (Err(_), Policy::Zero) => {
+                report.missing.push(idx + 1);
+                report.counted += 1;
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// the sum of the lines, errors located in `input`
+pub fn solve_with(input: &str, mapping: &Mapping) -> Result<i64, Error> {
+    score(input, mapping, Policy::Error).map(|report| report.sum)
+}
+
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    Parse(Error),
+    /// `StreamOptions` that can't read anything
+    InvalidOptions(String),
+}
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Io(e) => write!(f, "can't read the input: {}", e),
+            StreamError::Parse(e) => write!(f, "{}", e),
+            StreamError::InvalidOptions(reason) => write!(f, "invalid stream options: {}", reason),
+        }
+    }
+}
+impl std::error::Error for StreamError {}
+impl From<io::Error> for StreamError {
+    fn from(e: io::Error) -> Self {
+        StreamError::Io(e)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct StreamOptions {
+    /// workers summing chunks, 1 sums on the calling thread
+    pub threads: usize,
+    /// bytes per chunk, and between progress reports, at least 1
+    pub chunk_size: usize,
+}
+impl Default for StreamOptions {
+    fn default() -> Self {
+        StreamOptions {
+            threads: 1,
+            chunk_size: 1 << 20,
+        }
+    }
+}
+
+/// how far a stream has been read
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub struct Progress {
+    pub bytes: u64,
+    pub lines: usize,
+}
+
+/// the sum of `input`, whose first line is line `first_line`
+fn sum_lines(input: &str, mapping: &Mapping, first_line: usize) -> Result<i64, Error> {
+    let mut sum = 0;
+    for (idx, line) in input.lines().enumerate() {
+        let trimmed = line.trim();
+        sum += i64::from(
+            mapping
+                .solve_line(trimmed)
+                .map_err(|e| e.within(line, trimmed).at_line(first_line + idx))?,
+        );
+    }
+    Ok(sum)
+}
+
+/// `solve_with` over a reader, without holding the whole input in memory.
+/// `progress` is called after every `chunk_size` bytes and once at the end.
+pub fn solve_reader_with<R: BufRead>(
+    reader: R,
+    mapping: &Mapping,
+    options: StreamOptions,
+    progress: impl FnMut(Progress),
+) -> Result<i64, StreamError> {
+    if options.chunk_size == 0 {
+        return Err(StreamError::InvalidOptions(
+            "chunk_size must be at least 1".to_string(),
+        ));
+    }
+    if options.threads > 1 {
+        solve_chunks(reader, mapping, options, progress)
+    } else {
+        solve_lines(reader, mapping, options, progress)
+    }
+}
+
+fn solve_lines<R: BufRead>(
+    mut reader: R,
+    mapping: &Mapping,
+    options: StreamOptions,
+    mut progress: impl FnMut(Progress),
+) -> Result<i64, StreamError> {
+    let mut read = Progress::default();
+    let mut reported = 0;
+    let mut sum = 0;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let len = reader.read_line(&mut line)?;
+        if len == 0 {
+            break;
+        }
+        read.bytes += len as u64;
+        read.lines += 1;
+        sum += sum_lines(&line, mapping, read.lines).map_err(StreamError::Parse)?;
+
+        if read.bytes - reported >= options.chunk_size as u64 {
+            reported = read.bytes;
+            progress(read);
+        }
+    }
+    progress(read);
+    Ok(sum)
+}
+
+/// passes `chunk` with the number of its first line, and everything read so far, to `f`.
+/// a chunk is at least `chunk_size` bytes of whole lines, except the last one.
+fn read_chunks<R: BufRead>(
+    mut reader: R,
+    chunk_size: usize,
+    mut f: impl FnMut(usize, String, Progress),
+) -> io::Result<()> {
+    let mut read = Progress::default();
+    loop {
+        let mut chunk = Vec::with_capacity(chunk_size);
+        while chunk.len() < chunk_size {
+            if reader.read_until(b'\n', &mut chunk)? == 0 {
+                break;
+            }
+        }
+        if chunk.is_empty() {
+            return Ok(());
+        }
 
-    mapping[left.unwrap().1].1 * 10 + mapping[right.unwrap().1].1
+        let first_line = read.lines + 1;
+        read.bytes += chunk.len() as u64;
+        read.lines += chunk.iter().filter(|&&b| b == b'\n').count();
+        if chunk.last() != Some(&b'\n') {
+            read.lines += 1;
+        }
+        let chunk =
+            String::from_utf8(chunk).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        f(first_line, chunk, read);
+    }
 }
-mod part1 {
-    pub fn solve(input: &str) -> i32 {
+
+/// reads chunks of whole lines on this thread, and sums them on `options.threads` workers
+fn solve_chunks<R: BufRead>(
+    reader: R,
+    mapping: &Mapping,
+    options: StreamOptions,
+    mut progress: impl FnMut(Progress),
+) -> Result<i64, StreamError> {
+    let (chunk_tx, chunk_rx) = mpsc::sync_channel::<(usize, String)>(options.threads * 2);
+    let chunk_rx = Mutex::new(chunk_rx);
+    let (sum_tx, sum_rx) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..options.threads {
+            let sum_tx = sum_tx.clone();
+            let chunk_rx = &chunk_rx;
+            scope.spawn(move || loop {
+                let next = chunk_rx.lock().unwrap().recv();
+                let Ok((first_line, chunk)) = next else {
+                    break;
+                };
+                if sum_tx.send(sum_lines(&chunk, mapping, first_line)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sum_tx);
+
+        let read = read_chunks(reader, options.chunk_size, |first_line, chunk, read| {
+            // fails only if every worker panicked
+            chunk_tx.send((first_line, chunk)).unwrap();
+            progress(read);
+        });
+        drop(chunk_tx);
+        read.map_err(StreamError::Io)?;
+
+        // the first error of the input, whichever worker found it
         let mut sum = 0;
-        for line in input.lines() {
-            sum += solve_line(line.trim());
+        let mut first_error: Option<Error> = None;
+        for result in sum_rx {
+            match result {
+                Ok(n) => sum += n,
+                Err(e) if first_error.as_ref().is_none_or(|f| e.line < f.line) => {
+                    first_error = Some(e)
+                }
+                Err(_) => {}
+            }
+        }
+        match first_error {
+            Some(e) => Err(StreamError::Parse(e)),
+            None => Ok(sum),
+        }
+    })
+}
+
+/// how a line got its calibration value
+#[derive(Debug, PartialEq, Eq)]
+pub struct Explanation<'a> {
+    /// 1-based
+    pub line: usize,
+    pub text: &'a str,
+    /// the first and last tokens, offsets in `text`. `None` if the line has no value.
+    pub tokens: Option<(Token, Token)>,
+}
+impl Explanation<'_> {
+    pub fn value(&self) -> Option<i32> {
+        self.tokens
+            .map(|(first, last)| first.value * 10 + last.value)
+    }
+}
+
+pub fn explain<'a>(input: &'a str, mapping: &Mapping) -> Vec<Explanation<'a>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| {
+            let trimmed = line.trim();
+            let offset = line.len() - line.trim_start().len();
+            let moved = |t: Token| Token {
+                start: t.start + offset,
+                end: t.end + offset,
+                ..t
+            };
+            Explanation {
+                line: idx + 1,
+                text: line,
+                tokens: mapping
+                    .explain_line(trimmed)
+                    .ok()
+                    .map(|(first, last)| (moved(first), moved(last))),
+            }
+        })
+        .collect()
+}
+
+/// each line with its first and last token underlined, like a diagnostic
+pub fn format_explanations(explanations: &[Explanation]) -> String {
+    let width = explanations.last().map_or(1, |e| e.line.to_string().len());
+    let mut out = String::new();
+    for e in explanations {
+        out += &format!("{:>width$} | {}\n", e.line, e.text);
+        let Some((first, last)) = e.tokens else {
+            out += &format!("{:>width$} | no calibration value\n", "");
+            continue;
+        };
+
+        let column = |offset| e.text[..offset].chars().count();
+        let mut underline = vec![' '; column(first.end.max(last.end))];
+        for token in [first, last] {
+            underline[column(token.start)..column(token.end)].fill('^');
         }
-        sum
+        out += &format!(
+            "{:>width$} | {} [{}] {} .. [{}] {} = {}\n",
+            "",
+            underline.into_iter().collect::<String>(),
+            &e.text[first.start..first.end],
+            first.value,
+            &e.text[last.start..last.end],
+            last.value,
+            first.value * 10 + last.value
+        );
+    }
+    out
+}
+
+pub struct Day01;
+impl Solution for Day01 {
+    type Input = String;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(input.to_string())
     }
 
-    pub fn solve_line(input: &str) -> i32 {
-        const MAPPING: &[(&str, i32)] = &[
-            ("1", 1),
-            ("2", 2),
-            ("3", 3),
-            ("4", 4),
-            ("5", 5),
-            ("6", 6),
-            ("7", 7),
-            ("8", 8),
-            ("9", 9),
-        ];
+    fn part1(input: &Self::Input) -> Result<Answer, Error> {
+        part1::solve(input).map(Answer::from)
+    }
 
-        crate::solve_line_with_mapping(input, MAPPING)
+    fn part2(input: &Self::Input) -> Result<Answer, Error> {
+        part2::solve(input).map(Answer::from)
+    }
+}
+
+pub mod part1 {
+    use std::io::BufRead;
+
+    use super::{Mapping, OnceLock, StreamError, StreamOptions};
+    use crate::error::Error;
+
+    pub const MAPPING: &[(&str, i32)] = &[
+        ("1", 1),
+        ("2", 2),
+        ("3", 3),
+        ("4", 4),
+        ("5", 5),
+        ("6", 6),
+        ("7", 7),
+        ("8", 8),
+        ("9", 9),
+    ];
+
+    fn mapping() -> &'static Mapping {
+        static BUILT: OnceLock<Mapping> = OnceLock::new();
+        BUILT.get_or_init(|| Mapping::new(MAPPING))
+    }
+
+    pub fn solve(input: &str) -> Result<i64, Error> {
+        super::solve_with(input, mapping())
+    }
+
+    pub fn solve_line(input: &str) -> Result<i32, Error> {
+        mapping().solve_line(input)
+    }
+
+    pub fn solve_reader<R: BufRead>(reader: R) -> Result<i64, StreamError> {
+        super::solve_reader_with(reader, mapping(), StreamOptions::default(), |_| {})
     }
 
     #[cfg(test)]
@@ -60,10 +645,10 @@ mod part1 {
 
         #[test]
         fn test_solve_line() {
-            assert_eq!(solve_line("1abc2"), 12);
-            assert_eq!(solve_line("pqr3stu8vwx"), 38);
-            assert_eq!(solve_line("a1b2c3d4e5f"), 15);
-            assert_eq!(solve_line("treb7uchet"), 77);
+            assert_eq!(solve_line("1abc2"), Ok(12));
+            assert_eq!(solve_line("pqr3stu8vwx"), Ok(38));
+            assert_eq!(solve_line("a1b2c3d4e5f"), Ok(15));
+            assert_eq!(solve_line("treb7uchet"), Ok(77));
         }
 
         #[test]
@@ -72,49 +657,78 @@ mod part1 {
 pqr3stu8vwx
 a1b2c3d4e5f
 treb7uchet"#;
-            assert_eq!(solve(input), 142);
+            assert_eq!(solve(input), Ok(142));
+        }
+
+        #[test]
+        fn test_solve_error() {
+            let err = solve("1abc2\n  no digits\n").unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "line 2, column 3: expected a digit, found [no digits]"
+            );
+            assert_eq!((err.width, err.text.as_str()), (9, "  no digits"));
+
+            let err = solve_line("").unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "line 1, column 1: expected a digit, found end of line"
+            );
         }
 
         #[test]
         fn test_solve() {
-            let input = include_str!("day01.txt");
-            assert_eq!(solve(input), 53651);
-        }
-    }
-}
-
-mod part2 {
-    pub fn solve_line(input: &str) -> i32 {
-        const MAPPING: &[(&str, i32)] = &[
-            ("1", 1),
-            ("2", 2),
-            ("3", 3),
-            ("4", 4),
-            ("5", 5),
-            ("6", 6),
-            ("7", 7),
-            ("8", 8),
-            ("9", 9),
-            ("one", 1),
-            ("two", 2),
-            ("three", 3),
-            ("four", 4),
-            ("five", 5),
-            ("six", 6),
-            ("seven", 7),
-            ("eight", 8),
-            ("nine", 9),
-        ];
-
-        crate::solve_line_with_mapping(input, MAPPING)
-    }
-
-    pub fn solve(input: &str) -> i32 {
-        let mut sum = 0;
-        for line in input.lines() {
-            sum += solve_line(line.trim());
+            let Some(input) = crate::input::test_input(1) else {
+                return;
+            };
+            assert_eq!(solve(&input), Ok(53651));
+            assert_eq!(solve_reader(input.as_bytes()).unwrap(), 53651);
         }
-        sum
+    }
+}
+
+pub mod part2 {
+    use std::io::BufRead;
+
+    use super::{Mapping, OnceLock, StreamError, StreamOptions};
+    use crate::error::Error;
+
+    pub const MAPPING: &[(&str, i32)] = &[
+        ("1", 1),
+        ("2", 2),
+        ("3", 3),
+        ("4", 4),
+        ("5", 5),
+        ("6", 6),
+        ("7", 7),
+        ("8", 8),
+        ("9", 9),
+        ("one", 1),
+        ("two", 2),
+        ("three", 3),
+        ("four", 4),
+        ("five", 5),
+        ("six", 6),
+        ("seven", 7),
+        ("eight", 8),
+        ("nine", 9),
+    ];
+
+    fn mapping() -> &'static Mapping {
+        static BUILT: OnceLock<Mapping> = OnceLock::new();
+        BUILT.get_or_init(|| Mapping::new(MAPPING))
+    }
+
+    pub fn solve_line(input: &str) -> Result<i32, Error> {
+        mapping().solve_line(input)
+    }
+
+    pub fn solve_reader<R: BufRead>(reader: R) -> Result<i64, StreamError> {
+        super::solve_reader_with(reader, mapping(), StreamOptions::default(), |_| {})
+    }
+
+    pub fn solve(input: &str) -> Result<i64, Error> {
+        super::solve_with(input, mapping())
     }
 
     #[cfg(test)]
@@ -123,22 +737,22 @@ mod part2 {
 
         #[test]
         fn test_solve_line() {
-            assert_eq!(solve_line("two1nine"), 29);
-            assert_eq!(solve_line("eightwothree"), 83);
-            assert_eq!(solve_line("abcone2threexyz"), 13);
-            assert_eq!(solve_line("xtwone3four"), 24);
-            assert_eq!(solve_line("4nineeightseven2"), 42);
-            assert_eq!(solve_line("zoneight234"), 14);
-            assert_eq!(solve_line("7pqrstsixteen"), 76);
+            assert_eq!(solve_line("two1nine"), Ok(29));
+            assert_eq!(solve_line("eightwothree"), Ok(83));
+            assert_eq!(solve_line("abcone2threexyz"), Ok(13));
+            assert_eq!(solve_line("xtwone3four"), Ok(24));
+            assert_eq!(solve_line("4nineeightseven2"), Ok(42));
+            assert_eq!(solve_line("zoneight234"), Ok(14));
+            assert_eq!(solve_line("7pqrstsixteen"), Ok(76));
         }
 
         #[test]
         fn test_edge_case() {
-            assert_eq!(solve_line("threeeighthree"), 33);
-            assert_eq!(solve_line("eight8zlctbmsixhrvbpjb84nnmlcqkzrsix"), 86);
+            assert_eq!(solve_line("threeeighthree"), Ok(33));
+            assert_eq!(solve_line("eight8zlctbmsixhrvbpjb84nnmlcqkzrsix"), Ok(86));
             assert_eq!(
                 solve_line("seven8sevenptdlvvgssixvjvzpvsp7fivefourtwoned"),
-                71
+                Ok(71)
             );
         }
 
@@ -151,13 +765,205 @@ xtwone3four
 4nineeightseven2
 zoneight234
 7pqrstsixteen"#;
-            assert_eq!(solve(input), 281);
+            assert_eq!(solve(input), Ok(281));
         }
 
         #[test]
         fn test_solve() {
-            let input = include_str!("day01.txt");
-            assert_eq!(solve(input), 53894);
+            let Some(input) = crate::input::test_input(1) else {
+                return;
+            };
+            assert_eq!(solve(&input), Ok(53894));
+            assert_eq!(solve_reader(input.as_bytes()).unwrap(), 53894);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_english_is_part2() {
+        let vocabulary = Vocabulary::language(Language::English);
+        assert_eq!(vocabulary.entries(), part2::MAPPING);
+        assert_eq!(vocabulary.clone().with_zero(true).entries().len(), 20);
+    }
+
+    #[test]
+    fn test_languages() {
+        let solve = |vocabulary: &Vocabulary, line| vocabulary.mapping().solve_line(line);
+
+        let german = Vocabulary::language(Language::German);
+        assert_eq!(solve(&german, "xzweinsfünf"), Ok(25));
+        assert_eq!(solve(&german, "nullacht"), Ok(88));
+        assert_eq!(solve(&german.with_zero(true), "nullacht"), Ok(8));
+
+        let french = Vocabulary::language(Language::French).ignoring_case(true);
+        assert_eq!(solve(&french, "ZÉROtroisHuit"), Ok(38));
+
+        let korean = Vocabulary::language(Language::Korean);
+        assert_eq!(solve(&korean, "삼십칠"), Ok(37));
+        assert!(solve(&korean, "ten").is_err());
+
+        // words of one byte, or of one char, are still words
+        for mapping in [&[("x", 1)], &[("일", 1)]] {
+            let err = Mapping::new(mapping).solve_line("ten").unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "line 1, column 1: expected a digit or a digit word, found [ten]"
+            );
+        }
+
+        let spanish = Vocabulary::language(Language::Spanish);
+        assert_eq!(solve(&spanish, "Cuatro3cinco"), Ok(35));
+        assert_eq!(solve(&spanish.ignoring_case(true), "Cuatro3cinco"), Ok(45));
+
+        assert_eq!(Language::from_name("de"), Some(Language::German));
+        assert_eq!(Language::from_name("german"), None);
+    }
+
+    #[test]
+    fn test_fold_case() {
+        assert!(matches!(fold_case("abc"), Cow::Borrowed("abc")));
+        assert_eq!(fold_case("FÜNF Ab"), "fünf ab");
+        // the lowercase of the kelvin sign is one byte instead of three
+        assert_eq!(fold_case("\u{212a}A"), "\u{212a}a");
+    }
+
+    #[test]
+    fn test_score() {
+        let mapping = Mapping::new(part1::MAPPING);
+        let input = "1abc2\nnothing\n\ntreb7uchet";
+
+        let err = score(input, &mapping, Policy::Error).unwrap_err();
+        assert_eq!(err.line, 2);
+        assert_eq!(
+            score(input, &mapping, Policy::Skip),
+            Ok(Report {
+                sum: 89,
+                counted: 2,
+                missing: vec![2, 3],
+            })
+        );
+        let report = score(input, &mapping, Policy::Zero).unwrap();
+        assert_eq!((report.sum, report.counted), (89, 4));
+        assert_eq!(report.missing, vec![2, 3]);
+
+        assert_eq!(Policy::from_name("skip"), Some(Policy::Skip));
+        assert_eq!(Policy::default(), Policy::Error);
+    }
+
+    #[test]
+    fn test_explain() {
+        let mapping = Mapping::new(part2::MAPPING);
+        let explanations = explain("threeeighthree\n  xtwone3four\nnope", &mapping);
+
+        let (first, last) = explanations[0].tokens.unwrap();
+        assert_eq!(
+            (first.start, first.end, last.start, last.end),
+            (0, 5, 9, 14)
+        );
+        assert_eq!(mapping.entry(first.entry), ("three", 3));
+        assert_eq!(explanations[0].value(), Some(33));
+
+        let (first, last) = explanations[1].tokens.unwrap();
+        assert_eq!(&explanations[1].text[first.start..first.end], "two");
+        assert_eq!((last.start, last.end, last.entry), (9, 13, 12));
+        assert_eq!(explanations[2].value(), None);
+
+        assert_eq!(
+            format_explanations(&explanations),
+            "1 | threeeighthree
+  | ^^^^^    ^^^^^ [three] 3 .. [three] 3 = 33
+2 |   xtwone3four
+  |    ^^^   ^^^^ [two] 2 .. [four] 4 = 24
+3 | nope
+  | no calibration value
+"
+        );
+    }
+
+    #[test]
+    fn test_solve_reader() {
+        let input = "two1nine\neightwothree\nabcone2threexyz\nxtwone3four\n4nineeightseven2\nzoneight234\n7pqrstsixteen";
+        let mapping = Mapping::new(part2::MAPPING);
+        for threads in [1, 2, 3] {
+            for chunk_size in [1, 10, 1000] {
+                let options = StreamOptions {
+                    threads,
+                    chunk_size,
+                };
+                let mut reports = Vec::new();
+                let sum =
+                    solve_reader_with(input.as_bytes(), &mapping, options, |p| reports.push(p));
+                assert_eq!(sum.unwrap(), 281, "{:?}", options);
+                assert_eq!(
+                    reports.last(),
+                    Some(&Progress {
+                        bytes: input.len() as u64,
+                        lines: 7,
+                    })
+                );
+            }
+        }
+        assert_eq!(
+            part1::solve_reader("1abc2\r\n\ttreb7uchet\n".as_bytes()).unwrap(),
+            89
+        );
+    }
+
+    #[test]
+    fn test_solve_reader_error() {
+        let input = "1abc2\n3\n4\nnone\n5\nnothing\n";
+        let mapping = Mapping::new(part1::MAPPING);
+        for threads in [1, 4] {
+            let options = StreamOptions {
+                threads,
+                chunk_size: 2,
+            };
+            let err = solve_reader_with(input.as_bytes(), &mapping, options, |_| {}).unwrap_err();
+            let StreamError::Parse(err) = err else {
+                panic!("{}", err);
+            };
+            assert_eq!(err, part1::solve(input).unwrap_err());
         }
+
+        // an empty chunk would read nothing and sum to 0
+        let options = StreamOptions {
+            threads: 2,
+            chunk_size: 0,
+        };
+        let err = solve_reader_with(input.as_bytes(), &mapping, options, |_| {}).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid stream options: chunk_size must be at least 1"
+        );
+
+        let err = part2::solve_reader(&b"1\n\xff\n"[..]).unwrap_err();
+        assert!(matches!(err, StreamError::Io(e) if e.kind() == io::ErrorKind::InvalidData));
+    }
+
+    #[test]
+    fn test_parse_vocabulary() {
+        let vocabulary = Vocabulary::parse("# roman\n\nI 1\n  V 5\nIX 9\n").unwrap();
+        assert_eq!(vocabulary.words[1], ("V".to_string(), 5));
+        assert_eq!(vocabulary.mapping().solve_line("XIV"), Ok(15));
+        assert_eq!(
+            vocabulary.ignoring_case(true).mapping().solve_line("xiv"),
+            Ok(15)
+        );
+
+        let err = Vocabulary::parse("I 1\nX 10").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 3: expected a value from 0 to 9, found [10]"
+        );
+        let err = Vocabulary::parse("I").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 2: expected a value from 0 to 9, found end of line"
+        );
+        assert!(Vocabulary::parse("I 1 2").is_err());
     }
 }
@@ -1,60 +1,95 @@
-fn main() {
-    let input = include_str!("day02.txt");
+use crate::error::Error;
+use crate::solution::{Answer, Solution};
 
-    println!("part1: {}", part1::solve(input));
-    println!("part2: {}", part2::solve(input));
-}
-
-struct Game {
+pub struct Game {
     pub id: i32,
     pub cubes: Vec<Cube>,
 }
 
 #[derive(Debug, Eq, PartialEq)]
-struct Cube {
+pub struct Cube {
     pub red: i32,
     pub blue: i32,
     pub green: i32,
 }
 
-fn parse_game(input: &str) -> Game {
-    let (game_str, cubes_str) = input.split_once(':').unwrap();
+pub fn parse_game(input: &str) -> Result<Game, Error> {
+    let (game_str, cubes_str) = input
+        .split_once(':')
+        .ok_or_else(|| Error::missing(input, input, "`:` after the game id"))?;
 
-    let game_id = game_str
-        .split_whitespace()
-        .nth(1)
-        .unwrap()
-        .parse::<i32>()
-        .unwrap();
+    let game_id = match game_str.split_whitespace().nth(1) {
+        Some(id) => id
+            .parse::<i32>()
+            .map_err(|_| Error::unexpected(input, id, "a game id"))?,
+        None => {
+            let colon = &input[game_str.len()..game_str.len() + 1];
+            return Err(Error::unexpected(input, colon, "a game id"));
+        }
+    };
 
     let mut cubes = Vec::new();
     for cube_str in cubes_str.split(';') {
-        let cube = parse_cube(cube_str);
+        let cube = parse_cube(cube_str).map_err(|e| e.within(input, cube_str))?;
         cubes.push(cube);
     }
 
-    Game { id: game_id, cubes }
+    Ok(Game { id: game_id, cubes })
+}
+
+pub fn parse_games(input: &str) -> Result<Vec<Game>, Error> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| parse_game(line).map_err(|e| e.at_line(idx + 1)))
+        .collect()
 }
 
-fn parse_cube(input: &str) -> Cube {
+pub fn parse_cube(input: &str) -> Result<Cube, Error> {
     let mut red = 0;
     let mut blue = 0;
     let mut green = 0;
 
-    let cubes = input.split(',').map(|s| s.trim());
-    for c in cubes {
+    for c in input.split(',') {
         let mut w = c.split_whitespace();
-        let count = w.next().unwrap();
-        let color = w.next().unwrap();
+        let count_str = w
+            .next()
+            .ok_or_else(|| Error::missing(input, c, "a number of cubes"))?;
+        let count = count_str
+            .parse::<i32>()
+            .map_err(|_| Error::unexpected(input, count_str, "a number of cubes"))?;
+        let color = w
+            .next()
+            .ok_or_else(|| Error::missing(input, count_str, "a color"))?;
         match color {
-            "red" => red += count.parse::<i32>().unwrap(),
-            "blue" => blue += count.parse::<i32>().unwrap(),
-            "green" => green += count.parse::<i32>().unwrap(),
-            _ => panic!("unknown color [{}]", color),
+            "red" => red += count,
+            "blue" => blue += count,
+            "green" => green += count,
+            _ => return Err(Error::unexpected(input, color, "red, green or blue")),
+        }
+        if let Some(extra) = w.next() {
+            return Err(Error::unexpected(input, extra, "`,` or `;`"));
         }
     }
 
-    Cube { red, blue, green }
+    Ok(Cube { red, blue, green })
+}
+
+pub struct Day02;
+impl Solution for Day02 {
+    type Input = Vec<Game>;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        parse_games(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, Error> {
+        Ok(part1::solve_games(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, Error> {
+        Ok(part2::solve_games(input).into())
+    }
 }
 
 #[cfg(test)]
@@ -64,7 +99,7 @@ mod test {
     #[test]
     fn test_parse_game() {
         let input = "Game 1: 1 red, 1 blue; 2 red, 2 green; 10 red, 100 green, 1000 blue";
-        let game = parse_game(input);
+        let game = parse_game(input).unwrap();
         assert_eq!(game.id, 1);
         assert_eq!(game.cubes.len(), 3);
         assert_eq!(
@@ -92,10 +127,41 @@ mod test {
             }
         );
     }
+
+    #[test]
+    fn test_parse_error() {
+        let err = parse_games("Game 1: 1 red\nGame 2: 3 blue, 4 purple; 1 red")
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 19: expected red, green or blue, found [purple]"
+        );
+        assert_eq!(err.width, 6);
+
+        let err = parse_game("Game x: 1 red").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 6: expected a game id, found [x]"
+        );
+        let err = parse_game("Game 1: 1 red; 2").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 17: expected a color, found end of line"
+        );
+        let err = parse_game("Game 1: 1 red,, 2 blue").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 15: expected a number of cubes, found end of line"
+        );
+        assert!(parse_game("Game 1 1 red").is_err());
+        assert!(parse_cube("1 red 2").is_err());
+    }
 }
 
-mod part1 {
-    use crate::Game;
+pub mod part1 {
+    use super::Game;
+    use crate::error::Error;
 
     fn is_possible(game: &Game) -> bool {
         for cube in &game.cubes {
@@ -107,13 +173,11 @@ mod part1 {
         true
     }
 
-    pub fn solve(input: &str) -> i32 {
-        let mut games = Vec::new();
-        for line in input.lines() {
-            let game = crate::parse_game(line);
-            games.push(game);
-        }
+    pub fn solve(input: &str) -> Result<i32, Error> {
+        Ok(solve_games(&super::parse_games(input)?))
+    }
 
+    pub fn solve_games(games: &[Game]) -> i32 {
         games
             .iter()
             .filter_map(|game| {
@@ -138,19 +202,22 @@ Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
 Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
 Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green"#;
 
-            assert_eq!(solve(input), 8);
+            assert_eq!(solve(input), Ok(8));
         }
 
         #[test]
         fn test_solve() {
-            let input = include_str!("day02.txt");
-            assert_eq!(solve(input), 1931);
+            let Some(input) = crate::input::test_input(2) else {
+                return;
+            };
+            assert_eq!(solve(&input), Ok(1931));
         }
     }
 }
 
-mod part2 {
-    use crate::Game;
+pub mod part2 {
+    use super::Game;
+    use crate::error::Error;
 
     pub fn solve_game(game: &Game) -> i32 {
         let red = game.cubes.iter().max_by_key(|c| c.red).unwrap().red;
@@ -160,26 +227,24 @@ mod part2 {
         red * green * blue
     }
 
-    pub fn solve(input: &str) -> i32 {
-        let mut games = Vec::new();
-        for line in input.lines() {
-            let game = crate::parse_game(line);
-            games.push(game);
-        }
+    pub fn solve(input: &str) -> Result<i32, Error> {
+        Ok(solve_games(&super::parse_games(input)?))
+    }
 
+    pub fn solve_games(games: &[Game]) -> i32 {
         games.iter().map(solve_game).sum()
     }
 
     #[cfg(test)]
     mod test {
-        use crate::parse_game;
+        use crate::day02::parse_game;
 
         use super::*;
 
         #[test]
         fn test_solve_game() {
             let input = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green";
-            let game = parse_game(input);
+            let game = parse_game(input).unwrap();
             assert_eq!(solve_game(&game), 48);
         }
 
@@ -190,13 +255,15 @@ Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
 Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
 Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
 Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green"#;
-            assert_eq!(solve(input), 2286);
+            assert_eq!(solve(input), Ok(2286));
         }
 
         #[test]
         fn test_solve() {
-            let input = include_str!("day02.txt");
-            assert_eq!(solve(input), 83105);
+            let Some(input) = crate::input::test_input(2) else {
+                return;
+            };
+            assert_eq!(solve(&input), Ok(83105));
         }
     }
 }
@@ -1,23 +1,43 @@
-fn main() {
-    let input = include_str!("./day03.txt");
-    println!("day03 part1: {}", part1::solve(input));
-    println!("day03 part2: {}", part2::solve(input));
-}
+use crate::error::Error;
+use crate::solution::{Answer, Solution};
 
-struct Engine {
+pub struct Engine {
     pub lines: Vec<Vec<char>>,
 }
 impl Engine {
-    pub fn new(input: &str) -> Self {
-        let lines = input
-            .lines()
-            .map(|line| line.trim().chars().collect())
-            .collect();
-        Self { lines }
+    /// every row must be as wide as the first, and made of digits, `.` and symbols
+    pub fn new(input: &str) -> Result<Self, Error> {
+        let mut lines = Vec::new();
+        for (idx, line) in input.lines().enumerate() {
+            let row = line.trim();
+            let at_line = |e: Error| e.at_line(idx + 1);
+
+            if let Some((offset, c)) = row.char_indices().find(|(_, c)| !c.is_ascii_graphic()) {
+                let found = &row[offset..offset + c.len_utf8()];
+                return Err(at_line(Error::unexpected(
+                    line,
+                    found,
+                    "a digit, `.` or a symbol",
+                )));
+            }
+            if let Some(first) = lines.first().map(Vec::len) {
+                if row.len() != first {
+                    let reason = format!(
+                        "expected {} columns like line 1, found {}",
+                        first,
+                        row.len()
+                    );
+                    return Err(at_line(Error::invalid(line, row, reason)));
+                }
+            }
+
+            lines.push(row.chars().collect());
+        }
+        Ok(Self { lines })
     }
 
     pub fn width(&self) -> i32 {
-        self.lines[0].len() as i32
+        self.lines.first().map_or(0, |line| line.len() as i32)
     }
 
     pub fn height(&self) -> i32 {
@@ -30,7 +50,7 @@ impl Engine {
         }
 
         let c = self.lines[y as usize][x as usize];
-        if c.is_numeric() || c == '.' {
+        if c.is_ascii_digit() || c == '.' {
             false
         } else {
             true
@@ -56,13 +76,13 @@ impl Engine {
         for y in 0..self.height() {
             for x in 0..self.width() {
                 let c = self.lines[y as usize][x as usize];
-                if c.is_numeric() {
+                if let Some(digit) = c.to_digit(10) {
                     if let Some(n) = &mut num {
-                        n.value = n.value * 10 + c.to_digit(10).unwrap() as i32;
+                        n.value = n.value * 10 + digit as i32;
                         n.end_x = x;
                     } else {
                         num = Some(Number {
-                            value: c.to_digit(10).unwrap() as i32,
+                            value: digit as i32,
                             y,
                             start_x: x,
                             end_x: x,
@@ -94,7 +114,7 @@ struct Point {
 }
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
-struct Number {
+pub struct Number {
     pub value: i32,
     pub y: i32,
     pub start_x: i32,
@@ -114,6 +134,23 @@ impl Number {
     }
 }
 
+pub struct Day03;
+impl Solution for Day03 {
+    type Input = Engine;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Engine::new(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, Error> {
+        Ok(part1::solve_engine(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, Error> {
+        Ok(part2::solve_engine(input).into())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -122,16 +159,34 @@ mod test {
     fn test_load_engine() {
         let input_str = r#"..#...
 ....@$"#;
-        let engine = Engine::new(input_str);
+        let engine = Engine::new(input_str).unwrap();
         assert_eq!(engine.width(), 6);
         assert_eq!(engine.height(), 2);
     }
 
+    #[test]
+    fn test_load_error() {
+        let err = Engine::new("..#...\n..٣..$").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 3: expected a digit, `.` or a symbol, found [٣]"
+        );
+
+        let err = Engine::new("..#...\n  ..$").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 3: expected 6 columns like line 1, found 3"
+        );
+        assert_eq!(err.width, 3);
+
+        assert_eq!(Engine::new("").unwrap().width(), 0);
+    }
+
     #[test]
     fn test_get_numbers() {
         let input_str = r#"1#.23
         .4$56"#;
-        let engine = Engine::new(input_str);
+        let engine = Engine::new(input_str).unwrap();
         let numbers = engine.get_numbers();
         assert_eq!(numbers.len(), 4);
         assert_eq!(
@@ -173,11 +228,14 @@ mod test {
     }
 }
 
-mod part1 {
+pub mod part1 {
     use super::*;
 
-    pub fn solve(input: &str) -> i32 {
-        let engine = Engine::new(input);
+    pub fn solve(input: &str) -> Result<i32, Error> {
+        Ok(solve_engine(&Engine::new(input)?))
+    }
+
+    pub fn solve_engine(engine: &Engine) -> i32 {
         let numbers = engine.get_numbers();
         let sum = numbers
             .iter()
@@ -215,22 +273,27 @@ mod part1 {
 ......755.
 ...$.*....
 .664.598.."#;
-            assert_eq!(solve(input_str), 4361);
+            assert_eq!(solve(input_str), Ok(4361));
         }
 
         #[test]
         fn test_solve() {
-            let input_str = include_str!("./day03.txt");
-            assert_eq!(solve(input_str), 532428);
+            let Some(input_str) = crate::input::test_input(3) else {
+                return;
+            };
+            assert_eq!(solve(&input_str), Ok(532428));
         }
     }
 }
 
-mod part2 {
+pub mod part2 {
     use super::*;
 
-    pub fn solve(input: &str) -> i32 {
-        let engine = Engine::new(input);
+    pub fn solve(input: &str) -> Result<i32, Error> {
+        Ok(solve_engine(&Engine::new(input)?))
+    }
+
+    pub fn solve_engine(engine: &Engine) -> i32 {
         let numbers = engine.get_numbers();
         let mut sum = 0;
         for y in 0..engine.height() {
@@ -266,13 +329,15 @@ mod part2 {
 ......755.
 ...$.*....
 .664.598.."#;
-            assert_eq!(solve(input_str), 467835);
+            assert_eq!(solve(input_str), Ok(467835));
         }
 
         #[test]
         fn test_solve() {
-            let input_str = include_str!("./day03.txt");
-            assert_eq!(solve(input_str), 84051670);
+            let Some(input_str) = crate::input::test_input(3) else {
+                return;
+            };
+            assert_eq!(solve(&input_str), Ok(84051670));
         }
     }
 }
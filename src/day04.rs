@@ -1,11 +1,7 @@
 use std::collections::HashSet;
 
-fn main() {
-    let input = include_str!("day04.txt");
-
-    println!("part1: {}", part1::solve(input));
-    println!("part2: {}", part2::solve(input));
-}
+use crate::error::Error;
+use crate::solution::{Answer, Solution};
 
 pub struct Card {
     pub id: i32,
@@ -13,36 +9,66 @@ pub struct Card {
     pub winning_numbers: HashSet<i32>,
 }
 
-fn parse_card(input: &str) -> Card {
-    let (card_str, numbers_str) = input.split_once(':').unwrap();
-    let (winning_numbers_str, your_numbers_str) = numbers_str.split_once('|').unwrap();
+pub fn parse_card(input: &str) -> Result<Card, Error> {
+    let (card_str, numbers_str) = input
+        .split_once(':')
+        .ok_or_else(|| Error::missing(input, input, "`:` after the card id"))?;
+    let (winning_numbers_str, your_numbers_str) = numbers_str
+        .split_once('|')
+        .ok_or_else(|| Error::missing(input, input, "`|` between the numbers"))?;
+
+    let card_id = match card_str.split_whitespace().nth(1) {
+        Some(id) => id
+            .parse::<i32>()
+            .map_err(|_| Error::unexpected(input, id, "a card id"))?,
+        None => {
+            let colon = &input[card_str.len()..card_str.len() + 1];
+            return Err(Error::unexpected(input, colon, "a card id"));
+        }
+    };
+
+    let parse_numbers = |numbers_str: &str| {
+        numbers_str
+            .split_whitespace()
+            .map(|s| {
+                s.parse::<i32>()
+                    .map_err(|_| Error::unexpected(input, s, "a number"))
+            })
+            .collect::<Result<HashSet<_>, _>>()
+    };
+    let winning_numbers = parse_numbers(winning_numbers_str)?;
+    let your_numbers = parse_numbers(your_numbers_str)?;
+
+    Ok(Card {
+        id: card_id,
+        your_numbers,
+        winning_numbers,
+    })
+}
 
-    let card_id = card_str
-        .split_whitespace()
-        .nth(1)
-        .unwrap()
-        .parse::<i32>()
-        .unwrap();
+pub fn parse_cards(input: &str) -> Result<Vec<Card>, Error> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| parse_card(line).map_err(|e| e.at_line(idx + 1)))
+        .collect()
+}
 
-    let winning_numbers = winning_numbers_str
-        .split_whitespace()
-        .map(|s| s.trim().parse::<i32>().unwrap())
-        .collect::<HashSet<_>>();
+pub struct Day04;
+impl Solution for Day04 {
+    type Input = Vec<Card>;
 
-    let your_numbers = your_numbers_str
-        .split_whitespace()
-        .map(|s| s.trim().parse::<i32>().unwrap())
-        .collect::<HashSet<_>>();
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        parse_cards(input)
+    }
 
-    Card {
-        id: card_id,
-        your_numbers,
-        winning_numbers,
+    fn part1(input: &Self::Input) -> Result<Answer, Error> {
+        Ok(part1::solve_cards(input).into())
     }
-}
 
-fn parse_cards(input: &str) -> Vec<Card> {
-    input.lines().map(parse_card).collect()
+    fn part2(input: &Self::Input) -> Result<Answer, Error> {
+        Ok(part2::solve_cards(input).into())
+    }
 }
 
 #[cfg(test)]
@@ -52,7 +78,7 @@ mod test {
     #[test]
     fn test_parse_card() {
         let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53";
-        let card = parse_card(input);
+        let card = parse_card(input).unwrap();
         assert_eq!(card.id, 1);
 
         assert_eq!(card.your_numbers.len(), 8);
@@ -67,9 +93,30 @@ mod test {
             [41, 48, 83, 86, 17].into_iter().collect()
         );
     }
+
+    #[test]
+    fn test_parse_error() {
+        let err = parse_cards("Card 1: 1 2 | 3\nCard 2: 1 2 3").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 14: expected `|` between the numbers, found end of line"
+        );
+
+        let err = parse_card("Card 1: 41 4x | 83").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 12: expected a number, found [4x]"
+        );
+        assert_eq!(err.width, 2);
+        let err = parse_card("Card: 41 | 83").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 5: expected a card id, found [:]"
+        );
+    }
 }
 
-mod part1 {
+pub mod part1 {
     use super::*;
 
     pub fn solve_line(card: &Card) -> i32 {
@@ -84,8 +131,11 @@ mod part1 {
         2_i32.pow(count - 1)
     }
 
-    pub fn solve(input: &str) -> i32 {
-        let cards = parse_cards(input);
+    pub fn solve(input: &str) -> Result<i32, Error> {
+        Ok(solve_cards(&parse_cards(input)?))
+    }
+
+    pub fn solve_cards(cards: &[Card]) -> i32 {
         cards.iter().map(solve_line).sum()
     }
 
@@ -96,7 +146,7 @@ mod part1 {
         #[test]
         fn test_solve_line() {
             let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53";
-            let card = parse_card(input);
+            let card = parse_card(input).unwrap();
             assert_eq!(solve_line(&card), 8);
         }
 
@@ -108,22 +158,28 @@ Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
 Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11"#;
-            assert_eq!(solve(input), 13);
+            assert_eq!(solve(input), Ok(13));
         }
 
         #[test]
         fn test_solve() {
-            let input = include_str!("day04.txt");
-            assert_eq!(solve(input), 21821);
+            let Some(input) = crate::input::test_input(4) else {
+                return;
+            };
+            assert_eq!(solve(&input), Ok(21821));
         }
     }
 }
 
-mod part2 {
+pub mod part2 {
     use super::*;
 
-    pub fn solve(input: &str) -> i32 {
-        let card_matches = parse_cards(input)
+    pub fn solve(input: &str) -> Result<i32, Error> {
+        Ok(solve_cards(&parse_cards(input)?))
+    }
+
+    pub fn solve_cards(cards: &[Card]) -> i32 {
+        let card_matches = cards
             .iter()
             .map(|c| c.your_numbers.intersection(&c.winning_numbers).count())
             .collect::<Vec<_>>();
@@ -152,13 +208,15 @@ Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
 Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11"#;
-            assert_eq!(solve(input), 30);
+            assert_eq!(solve(input), Ok(30));
         }
 
         #[test]
         fn test_solve() {
-            let input = include_str!("day04.txt");
-            assert_eq!(solve(input), 5539496);
+            let Some(input) = crate::input::test_input(4) else {
+                return;
+            };
+            assert_eq!(solve(&input), Ok(5539496));
         }
     }
 }
@@ -1,60 +1,955 @@
-fn main() {}
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::ops::Range;
 
-struct Maps {
-    src_name: String,
-    dest_name: String,
-    elements: Vec<MapElement>,
+use crate::error::{Error, ErrorKind};
+use crate::solution::{Answer, Solution};
+
+pub struct Day05;
+impl Solution for Day05 {
+    type Input = Almanac;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Almanac::new(input).map_err(|e| e.into_error(input))
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, Error> {
+        part1::solve_almanac(input).map(Answer::from)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, Error> {
+        part2::solve_almanac(input).map(Answer::from)
+    }
+}
+
+pub struct Almanac {
+    pub seeds: Vec<u64>,
+    /// the `seeds:` line, and where each seed is in it
+    seeds_line: String,
+    seed_spans: Vec<Range<usize>>,
+    /// in the order of the input
+    pub maps: Vec<Maps>,
+    /// `maps` walked backwards
+    inverses: Vec<Maps>,
+    graph: CategoryGraph,
+    /// the steps from seeds to locations, empty without maps
+    chain: Vec<Step>,
+}
+impl Almanac {
+    pub fn new(input: &str) -> Result<Self, ParseError> {
+        Self::with_layout(input, ColumnLayout::default())
+    }
+
+    /// same as `new`, but map rows are read in `layout`
+    pub fn with_layout(input: &str, layout: ColumnLayout) -> Result<Self, ParseError> {
+        let mut lines = input.lines().enumerate().map(|(idx, line)| (idx + 1, line));
+
+        let (_, seeds_line) = lines.next().unwrap_or((1, ""));
+        let Some(seeds_str) = seeds_line.trim_end().strip_prefix("seeds:") else {
+            return Err(ParseError {
+                line: 1,
+                column: 1,
+                text: seeds_line.to_string(),
+                kind: ParseErrorKind::MissingSeeds,
+            });
+        };
+        let seed_tokens = tokens(seeds_str)
+            .into_iter()
+            .map(|(offset, s)| ("seeds:".len() + offset, s))
+            .collect::<Vec<_>>();
+        let seeds = seed_tokens
+            .iter()
+            .map(|&(offset, s)| parse_number(seeds_line, offset, s))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut maps: Vec<Maps> = Vec::new();
+        // line number and text of the header of each map
+        let mut headers = Vec::new();
+        for (line_no, line) in lines {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            if line.trim_end().ends_with(':') {
+                let next = Maps::init(line)
+                    .map_err(|e| e.at_line(line_no))?
+                    .with_layout(layout);
+                maps.push(next);
+                headers.push((line_no, line));
+            } else {
+                let Some(last) = maps.last_mut() else {
+                    return Err(ParseError {
+                        line: line_no,
+                        column: 1,
+                        text: line.to_string(),
+                        kind: ParseErrorKind::ElementBeforeHeader,
+                    });
+                };
+                last.add_element(line).map_err(|e| e.at_line(line_no))?;
+            }
+        }
+
+        // errors of the graph point at the header of the map that breaks it
+        let route_error = |e: RouteError| {
+            let names = match &e {
+                RouteError::DuplicateEdge { src, dest } => Some((src.as_str(), dest.as_str())),
+                RouteError::Cycle(cycle) => match cycle.as_slice() {
+                    [.., src, dest] => Some((src.as_str(), dest.as_str())),
+                    _ => None,
+                },
+                _ => None,
+            };
+            let header = names.and_then(|(src, dest)| {
+                let idx = maps
+                    .iter()
+                    .rposition(|m| m.src_name == src && m.dest_name == dest)?;
+                Some(headers[idx])
+            });
+            let (line, text) = header.unwrap_or((1, ""));
+            let offset = text.len() - text.trim_start().len();
+            ParseError {
+                line,
+                column: column_of(text, offset),
+                text: text.trim().to_string(),
+                kind: ParseErrorKind::Route(e),
+            }
+        };
+        let graph = CategoryGraph::new(&maps).map_err(route_error)?;
+        let chain = if maps.is_empty() {
+            Vec::new()
+        } else {
+            graph.route("seed", "location").map_err(route_error)?
+        };
+
+        Ok(Almanac {
+            seeds,
+            seeds_line: seeds_line.to_string(),
+            seed_spans: seed_tokens
+                .iter()
+                .map(|&(offset, s)| offset..offset + s.len())
+                .collect(),
+            inverses: maps.iter().map(Maps::invert).collect(),
+            maps,
+            graph,
+            chain,
+        })
+    }
+
+    /// the map of `step`, in its direction
+    fn step_maps(&self, step: &Step) -> &Maps {
+        if step.inverse {
+            &self.inverses[step.map]
+        } else {
+            &self.maps[step.map]
+        }
+    }
+
+    pub fn get_location(&self, seed: u64) -> u64 {
+        self.chain
+            .iter()
+            .fold(seed, |v, step| self.step_maps(step).get_dest(v))
+    }
+
+    pub fn get_location_ranges(&self, seeds: &[Range<u64>]) -> Vec<Range<u64>> {
+        self.chain.iter().fold(seeds.to_vec(), |ranges, step| {
+            self.step_maps(step).get_dest_ranges(&ranges)
+        })
+    }
+
+    /// walks the chain backwards and returns every seed that ends up in `location`
+    pub fn get_seeds(&self, location: u64) -> Vec<u64> {
+        self.chain
+            .iter()
+            .rev()
+            .fold(vec![location], |values, step| {
+                let maps = self.step_maps(step);
+                values.iter().flat_map(|&v| maps.get_srcs(v)).collect()
+            })
+    }
+
+    /// the lowest location that can be reached from any seed in `seeds`, `None` if there are none.
+    /// walks the pieces of the composed map by location, the breakpoints of its inverse,
+    /// and stops once a piece starts above the lowest location found.
+    pub fn find_lowest_location(&self, seeds: &[Range<u64>]) -> Option<u64> {
+        let mut pieces = self.compose_all().split(&(0..u64::MAX));
+        pieces.sort_by_key(|&(_, dest)| dest);
+
+        let mut lowest: Option<u64> = None;
+        for (src, dest) in pieces {
+            if lowest.is_some_and(|l| l <= dest) {
+                break;
+            }
+            let first_seed = seeds
+                .iter()
+                .map(|r| r.start.max(src.start)..r.end.min(src.end))
+                .filter(|overlap| !overlap.is_empty())
+                .map(|overlap| overlap.start)
+                .min();
+            if let Some(seed) = first_seed {
+                let location = dest + (seed - src.start);
+                lowest = Some(lowest.map_or(location, |l| l.min(location)));
+            }
+        }
+        lowest
+    }
+
+    /// converts `value` from category `from` to category `to`, walking maps backwards where needed
+    pub fn convert(&self, from: &str, to: &str, value: u64) -> Result<u64, RouteError> {
+        let route = self.graph.route(from, to)?;
+        Ok(route
+            .iter()
+            .fold(value, |v, step| self.step_maps(step).get_dest(v)))
+    }
+
+    /// the whole seed to location chain as a single map.
+    /// without any maps, that is the identity from seeds to seeds.
+    pub fn compose_all(&self) -> Maps {
+        let mut steps = self.chain.iter().map(|step| self.step_maps(step));
+        let Some(first) = steps.next() else {
+            return Maps {
+                src_name: "seed".to_string(),
+                dest_name: "seed".to_string(),
+                layout: ColumnLayout::default(),
+                elements: Vec::new(),
+            };
+        };
+        steps.fold(first.clone(), |acc, maps| acc.compose(maps))
+    }
+}
+
+/// what the solvers return for an almanac without seeds
+fn no_seeds() -> Error {
+    const SEEDS: &str = "seeds:";
+    Error::missing(SEEDS, SEEDS, "a seed")
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RouteError {
+    UnknownCategory(String),
+    Unreachable { from: String, to: String },
+    Cycle(Vec<String>),
+    DuplicateEdge { src: String, dest: String },
+}
+impl fmt::Display for RouteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RouteError::UnknownCategory(name) => write!(f, "unknown category [{}]", name),
+            RouteError::Unreachable { from, to } => {
+                write!(f, "category [{}] is not reachable from [{}]", to, from)
+            }
+            RouteError::Cycle(names) => write!(f, "maps form a cycle: {}", names.join(" -> ")),
+            RouteError::DuplicateEdge { src, dest } => {
+                write!(f, "more than one {}-to-{} map", src, dest)
+            }
+        }
+    }
+}
+impl std::error::Error for RouteError {}
+
+/// one conversion step: index of the map to use, and whether it is walked backwards
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Step {
+    pub map: usize,
+    pub inverse: bool,
+}
+
+/// categories connected by the maps which convert between them
+pub struct CategoryGraph {
+    // category name -> (neighbor, step to get there)
+    edges: HashMap<String, Vec<(String, Step)>>,
+}
+impl CategoryGraph {
+    pub fn new(maps: &[Maps]) -> Result<Self, RouteError> {
+        let mut edges: HashMap<String, Vec<(String, Step)>> = HashMap::new();
+        for (idx, maps) in maps.iter().enumerate() {
+            let forward = edges.entry(maps.src_name.clone()).or_default();
+            if forward
+                .iter()
+                .any(|(n, s)| n == &maps.dest_name && !s.inverse)
+            {
+                return Err(RouteError::DuplicateEdge {
+                    src: maps.src_name.clone(),
+                    dest: maps.dest_name.clone(),
+                });
+            }
+            forward.push((
+                maps.dest_name.clone(),
+                Step {
+                    map: idx,
+                    inverse: false,
+                },
+            ));
+
+            edges.entry(maps.dest_name.clone()).or_default().push((
+                maps.src_name.clone(),
+                Step {
+                    map: idx,
+                    inverse: true,
+                },
+            ));
+        }
+
+        let graph = CategoryGraph { edges };
+        if let Some(cycle) = graph.find_cycle() {
+            return Err(RouteError::Cycle(cycle));
+        }
+        Ok(graph)
+    }
+
+    /// finds a cycle of forward maps, returned as the category names along it
+    fn find_cycle(&self) -> Option<Vec<String>> {
+        // 1: on the current path, 2: done
+        let mut state: HashMap<&str, u8> = HashMap::new();
+        let mut path: Vec<&str> = Vec::new();
+
+        fn visit<'a>(
+            graph: &'a CategoryGraph,
+            name: &'a str,
+            state: &mut HashMap<&'a str, u8>,
+            path: &mut Vec<&'a str>,
+        ) -> Option<Vec<String>> {
+            match state.get(name) {
+                Some(1) => {
+                    let start = path.iter().position(|&n| n == name).unwrap();
+                    let mut cycle = path[start..]
+                        .iter()
+                        .map(|n| n.to_string())
+                        .collect::<Vec<_>>();
+                    cycle.push(name.to_string());
+                    return Some(cycle);
+                }
+                Some(_) => return None,
+                None => {}
+            }
+
+            state.insert(name, 1);
+            path.push(name);
+            for (next, step) in &graph.edges[name] {
+                if !step.inverse {
+                    if let Some(cycle) = visit(graph, next, state, path) {
+                        return Some(cycle);
+                    }
+                }
+            }
+            path.pop();
+            state.insert(name, 2);
+            None
+        }
+
+        let mut names = self.edges.keys().collect::<Vec<_>>();
+        names.sort();
+        names
+            .into_iter()
+            .find_map(|name| visit(self, name, &mut state, &mut path))
+    }
+
+    /// shortest route from `from` to `to`.
+    /// forward maps only if possible, otherwise maps may also be walked backwards.
+    pub fn route(&self, from: &str, to: &str) -> Result<Vec<Step>, RouteError> {
+        for name in [from, to] {
+            if !self.edges.contains_key(name) {
+                return Err(RouteError::UnknownCategory(name.to_string()));
+            }
+        }
+
+        self.search(from, to, false)
+            .or_else(|| self.search(from, to, true))
+            .ok_or_else(|| RouteError::Unreachable {
+                from: from.to_string(),
+                to: to.to_string(),
+            })
+    }
+
+    fn search(&self, from: &str, to: &str, allow_inverse: bool) -> Option<Vec<Step>> {
+        // category name -> (previous category, step taken)
+        let mut prev: HashMap<&str, Option<(&str, Step)>> = HashMap::new();
+        let mut queue = VecDeque::new();
+        prev.insert(from, None);
+        queue.push_back(from);
+
+        while let Some(name) = queue.pop_front() {
+            if name == to {
+                let mut steps = Vec::new();
+                let mut cur = name;
+                while let Some(&Some((p, step))) = prev.get(cur) {
+                    steps.push(step);
+                    cur = p;
+                }
+                steps.reverse();
+                return Some(steps);
+            }
+
+            for (next, step) in &self.edges[name] {
+                if step.inverse && !allow_inverse {
+                    continue;
+                }
+                if !prev.contains_key(next.as_str()) {
+                    prev.insert(next, Some((name, *step)));
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// which column of a map row holds each value
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct ColumnLayout {
+    pub dest: usize,
+    pub src: usize,
+    pub range: usize,
+}
+impl ColumnLayout {
+    /// `<dest> <src> <range>`, as in the puzzle input
+    pub const PUZZLE: ColumnLayout = ColumnLayout {
+        dest: 0,
+        src: 1,
+        range: 2,
+    };
+
+    /// parses the column names in order, e.g. `src,dest,range`
+    pub fn parse(input: &str) -> Option<Self> {
+        let names = input.split(',').map(|s| s.trim()).collect::<Vec<_>>();
+        let position = |name| names.iter().position(|&n| n == name);
+        if names.len() != 3 {
+            return None;
+        }
+
+        Some(ColumnLayout {
+            dest: position("dest")?,
+            src: position("src")?,
+            range: position("range")?,
+        })
+    }
+}
+impl Default for ColumnLayout {
+    fn default() -> Self {
+        ColumnLayout::PUZZLE
+    }
+}
+impl fmt::Display for ColumnLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names = [""; 3];
+        names[self.dest] = "<dest>";
+        names[self.src] = "<src>";
+        names[self.range] = "<range>";
+        write!(f, "{}", names.join(" "))
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Maps {
+    pub src_name: String,
+    pub dest_name: String,
+    pub layout: ColumnLayout,
+    pub elements: Vec<MapElement>,
 }
 impl Maps {
-    pub fn init(input: &str) -> Self {
-        let idx = input.trim().rfind(" ").unwrap();
-        let parsed = input[..idx].split('-').collect::<Vec<_>>();
-        let src_name = parsed[0].to_string();
-        let dest_name = parsed[2].to_string();
-        Maps {
-            src_name,
-            dest_name,
-            elements: Vec::new(),
+    /// parses a `<src>-to-<dest> map:` header, rows are read in the puzzle layout
+    pub fn init(input: &str) -> Result<Self, ParseError> {
+        let invalid_header = |offset: usize, text: &str| ParseError {
+            line: 1,
+            column: column_of(input, offset),
+            text: text.to_string(),
+            kind: ParseErrorKind::InvalidHeader,
+        };
+
+        let (name_offset, name) = match tokens(input).as_slice() {
+            [(offset, name), (_, suffix)] if suffix.ends_with(':') => (*offset, *name),
+            [(offset, _), ..] => return Err(invalid_header(*offset, input.trim())),
+            [] => return Err(invalid_header(0, input)),
+        };
+
+        let parsed = name.split('-').collect::<Vec<_>>();
+        match parsed.as_slice() {
+            [src, "to", dest] if !src.is_empty() && !dest.is_empty() => Ok(Maps {
+                src_name: src.to_string(),
+                dest_name: dest.to_string(),
+                layout: ColumnLayout::default(),
+                elements: Vec::new(),
+            }),
+            _ => Err(invalid_header(name_offset, name)),
+        }
+    }
+
+    /// rows added after this are read in `layout`
+    pub fn with_layout(mut self, layout: ColumnLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// parses a row in the layout of this map and inserts it
+    pub fn add_element(&mut self, input: &str) -> Result<(), ParseError> {
+        let parsed = tokens(input);
+        if parsed.len() < 3 {
+            return Err(ParseError {
+                line: 1,
+                column: column_of(input, input.trim_end().len()),
+                text: input.trim().to_string(),
+                kind: ParseErrorKind::MissingColumn(self.layout),
+            });
+        }
+        if let Some(&(offset, extra)) = parsed.get(3) {
+            return Err(ParseError {
+                line: 1,
+                column: column_of(input, offset),
+                text: extra.to_string(),
+                kind: ParseErrorKind::ExtraColumn,
+            });
         }
+
+        let column = |i: usize| {
+            let (offset, s) = parsed[i];
+            parse_number(input, offset, s)
+        };
+        let dest = column(self.layout.dest)?;
+        let src = column(self.layout.src)?;
+        let range = column(self.layout.range)?;
+
+        self.insert_element(MapElement { dest, src, range })
+            .map_err(|e| ParseError {
+                line: 1,
+                column: column_of(input, parsed[0].0),
+                text: input.trim().to_string(),
+                kind: ParseErrorKind::Element(e),
+            })
     }
 
-    pub fn add_element(&mut self, input: &str) {
-        let parsed = input.trim().split(' ').collect::<Vec<_>>();
-        let src = parsed[0].trim().parse::<u64>().unwrap();
-        let dest = parsed[1].trim().parse::<u64>().unwrap();
-        let range = parsed[2].trim().parse::<u64>().unwrap();
-        self.elements.push(MapElement { dest, src, range });
+    /// inserts `element` keeping `elements` sorted by `src`.
+    /// fails if its range is empty, overlaps with another element, or either end doesn't fit in u64.
+    /// an empty element could share its `src` with another one and shadow it in `get_dest`.
+    pub fn insert_element(&mut self, element: MapElement) -> Result<(), ElementError> {
+        if element.range == 0 {
+            return Err(ElementError::Empty(element));
+        }
+        if element.src.checked_add(element.range).is_none()
+            || element.dest.checked_add(element.range).is_none()
+        {
+            return Err(ElementError::Overflow(element));
+        }
+
+        let idx = self.elements.partition_point(|e| e.src < element.src);
+        let prev = idx.checked_sub(1).map(|i| &self.elements[i]);
+        let next = self.elements.get(idx);
+        for other in prev.into_iter().chain(next) {
+            if other.overlaps(&element) {
+                return Err(ElementError::Overlap(other.clone(), element));
+            }
+        }
+
+        self.elements.insert(idx, element);
+        Ok(())
     }
 
     pub fn get_dest(&self, src: u64) -> u64 {
-        for element in &self.elements {
-            if let Some(dest) = element.get_dest(src) {
-                return dest;
+        // the only element which can contain `src` is the last one starting at or before it
+        let idx = self.elements.partition_point(|e| e.src <= src);
+        idx.checked_sub(1)
+            .and_then(|i| self.elements[i].get_dest(src))
+            .unwrap_or(src)
+    }
+
+    /// maps every half-open range in `ranges` through this map.
+    /// ranges are split at element boundaries, and parts not covered by any element are passed through unchanged.
+    pub fn get_dest_ranges(&self, ranges: &[Range<u64>]) -> Vec<Range<u64>> {
+        ranges
+            .iter()
+            .flat_map(|range| self.split(range))
+            .map(|(src, dest)| dest..dest + (src.end - src.start))
+            .collect()
+    }
+
+    /// returns every source which is mapped to `dest`.
+    /// `dest` itself is a candidate only when it is not covered by any element, since it is mapped as is then.
+    pub fn get_srcs(&self, dest: u64) -> Vec<u64> {
+        let mut srcs = self
+            .elements
+            .iter()
+            .filter_map(|element| element.invert().get_dest(dest))
+            .collect::<Vec<_>>();
+
+        if !self.elements.iter().any(|e| e.check_src_range(dest)) {
+            srcs.push(dest);
+        }
+
+        srcs
+    }
+
+    /// swaps source and destination of every element.
+    /// values in the gaps are still mapped to themselves, so the result is the exact inverse
+    /// only if the elements' destinations cover the same values as their sources (as in the puzzle input).
+    /// use `get_srcs` to get every candidate otherwise.
+    pub fn invert(&self) -> Maps {
+        let mut elements = self
+            .elements
+            .iter()
+            .map(MapElement::invert)
+            .collect::<Vec<_>>();
+        elements.sort_by_key(|e| e.src);
+
+        Maps {
+            src_name: self.dest_name.clone(),
+            dest_name: self.src_name.clone(),
+            layout: self.layout,
+            elements,
+        }
+    }
+
+    /// splits `range` at element boundaries.
+    /// returns the source pieces in order, each with the destination its start is mapped to.
+    fn split(&self, range: &Range<u64>) -> Vec<(Range<u64>, u64)> {
+        if range.is_empty() {
+            return Vec::new();
+        }
+
+        // source parts of `range` covered by some element, in order
+        let first = self
+            .elements
+            .partition_point(|e| e.src + e.range <= range.start);
+        let covered = self.elements[first..]
+            .iter()
+            .take_while(|e| e.src < range.end)
+            .filter_map(|element| {
+                let overlap = element.get_src_overlap(range)?;
+                let dest = element.get_dest_range(&overlap).start;
+                Some((overlap, dest))
+            });
+
+        // fill the gaps between covered parts with identity pieces
+        let mut pieces = Vec::new();
+        let mut start = range.start;
+        for (c, dest) in covered {
+            if c.start > start {
+                pieces.push((start..c.start, start));
             }
+            start = start.max(c.end);
+            pieces.push((c, dest));
+        }
+        if start < range.end {
+            pieces.push((start..range.end, start));
         }
 
-        src
+        pieces
+    }
+
+    /// fuses `self` followed by `next` into a single map from `self.src_name` to `next.dest_name`.
+    pub fn compose(&self, next: &Maps) -> Maps {
+        let mut elements: Vec<MapElement> = Vec::new();
+        for (src, mid) in self.split(&(0..u64::MAX)) {
+            let mid_range = mid..mid + (src.end - src.start);
+            for (next_src, dest) in next.split(&mid_range) {
+                let element = MapElement {
+                    dest,
+                    src: src.start + (next_src.start - mid),
+                    range: next_src.end - next_src.start,
+                };
+
+                // identity pieces are implied by the gaps
+                if element.dest == element.src {
+                    continue;
+                }
+
+                // merge with the previous piece when both continue each other
+                match elements.last_mut() {
+                    Some(last)
+                        if last.src + last.range == element.src
+                            && last.dest + last.range == element.dest =>
+                    {
+                        last.range += element.range;
+                    }
+                    _ => elements.push(element),
+                }
+            }
+        }
+
+        Maps {
+            src_name: self.src_name.clone(),
+            dest_name: next.dest_name.clone(),
+            layout: self.layout,
+            elements,
+        }
     }
 }
 
-#[derive(Debug, PartialEq)]
-struct MapElement {
-    dest: u64,
-    src: u64,
-    range: u64,
+/// writes the header and rows back in the layout they were read in
+impl std::fmt::Display for Maps {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}-to-{} map:", self.src_name, self.dest_name)?;
+        for element in &self.elements {
+            let mut columns = [0; 3];
+            columns[self.layout.dest] = element.dest;
+            columns[self.layout.src] = element.src;
+            columns[self.layout.range] = element.range;
+            writeln!(f, "{} {} {}", columns[0], columns[1], columns[2])?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct MapElement {
+    pub dest: u64,
+    pub src: u64,
+    pub range: u64,
 }
 impl MapElement {
     pub fn check_src_range(&self, v: u64) -> bool {
-        v >= self.src && v < self.src + self.range
+        v >= self.src && v - self.src < self.range
     }
 
     pub fn get_dest(&self, v: u64) -> Option<u64> {
         if self.check_src_range(v) {
-            Some(self.dest + v - self.src)
+            Some(self.dest + (v - self.src))
+        } else {
+            None
+        }
+    }
+
+    pub fn overlaps(&self, other: &MapElement) -> bool {
+        self.src < other.src + other.range && other.src < self.src + self.range
+    }
+
+    pub fn get_src_overlap(&self, range: &Range<u64>) -> Option<Range<u64>> {
+        let start = range.start.max(self.src);
+        let end = range.end.min(self.src + self.range);
+        if start < end {
+            Some(start..end)
         } else {
             None
         }
     }
+
+    pub fn invert(&self) -> MapElement {
+        MapElement {
+            dest: self.src,
+            src: self.dest,
+            range: self.range,
+        }
+    }
+
+    /// `src_range` must be inside of this element's source range
+    pub fn get_dest_range(&self, src_range: &Range<u64>) -> Range<u64> {
+        (self.dest + src_range.start - self.src)..(self.dest + src_range.end - self.src)
+    }
+}
+
+impl fmt::Display for MapElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.dest, self.src, self.range)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ElementError {
+    Empty(MapElement),
+    Overlap(MapElement, MapElement),
+    Overflow(MapElement),
+}
+impl fmt::Display for ElementError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ElementError::Overlap(a, b) => {
+                write!(f, "source range of [{}] overlaps with [{}]", b, a)
+            }
+            ElementError::Empty(e) => write!(f, "[{}] maps an empty range", e),
+            ElementError::Overflow(e) => write!(f, "[{}] overflows u64", e),
+        }
+    }
+}
+impl std::error::Error for ElementError {}
+
+#[derive(Debug, PartialEq)]
+pub enum ParseErrorKind {
+    MissingSeeds,
+    InvalidHeader,
+    Route(RouteError),
+    ElementBeforeHeader,
+    MissingColumn(ColumnLayout),
+    ExtraColumn,
+    InvalidNumber,
+    Element(ElementError),
+}
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseErrorKind::MissingSeeds => write!(f, "expected `seeds: <numbers>`"),
+            ParseErrorKind::InvalidHeader => write!(f, "expected `<src>-to-<dest> map:`"),
+            ParseErrorKind::Route(e) => write!(f, "{}", e),
+            ParseErrorKind::ElementBeforeHeader => write!(f, "map row before any map header"),
+            ParseErrorKind::MissingColumn(layout) => write!(f, "expected `{}`", layout),
+            ParseErrorKind::ExtraColumn => write!(f, "unexpected column"),
+            ParseErrorKind::InvalidNumber => write!(f, "invalid number"),
+            ParseErrorKind::Element(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// `line` and `column` are 1-based
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub kind: ParseErrorKind,
+}
+impl ParseError {
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.line = line;
+        self
+    }
+
+    /// the crate error for this error in `input`, which shows the whole line
+    pub fn into_error(self, input: &str) -> Error {
+        let line = input.lines().nth(self.line - 1).unwrap_or("");
+        let at_column = line.chars().skip(self.column - 1).collect::<String>();
+        let width = match at_column.starts_with(&self.text) {
+            true => self.text.chars().count().max(1),
+            false => 1,
+        };
+
+        let unexpected = |expected: String, found: Option<String>| ErrorKind::Unexpected {
+            expected,
+            found: found.filter(|f| !f.is_empty()),
+        };
+        let kind = match self.kind {
+            ParseErrorKind::MissingSeeds => {
+                unexpected("`seeds: <numbers>`".to_string(), Some(self.text))
+            }
+            ParseErrorKind::InvalidHeader => {
+                unexpected("`<src>-to-<dest> map:`".to_string(), Some(self.text))
+            }
+            ParseErrorKind::MissingColumn(layout) => unexpected(format!("`{}`", layout), None),
+            ParseErrorKind::InvalidNumber => unexpected("a number".to_string(), Some(self.text)),
+            kind => ErrorKind::Invalid(kind.to_string()),
+        };
+
+        Error {
+            day: None,
+            line: self.line,
+            column: self.column,
+            text: line.to_string(),
+            width,
+            kind,
+        }
+    }
+}
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {} [{}]",
+            self.line, self.column, self.kind, self.text
+        )
+    }
+}
+impl std::error::Error for ParseError {}
+
+/// whitespace separated tokens with their byte offsets
+fn tokens(input: &str) -> Vec<(usize, &str)> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    for (idx, c) in input.char_indices() {
+        match (start, c.is_whitespace()) {
+            (None, false) => start = Some(idx),
+            (Some(s), true) => {
+                tokens.push((s, &input[s..idx]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        tokens.push((s, &input[s..]));
+    }
+    tokens
+}
+
+/// 1-based column of the byte `offset` in `line`
+fn column_of(line: &str, offset: usize) -> usize {
+    line[..offset].chars().count() + 1
+}
+
+/// parses `s`, found at the byte `offset` in `line`
+pub fn parse_number(line: &str, offset: usize, s: &str) -> Result<u64, ParseError> {
+    s.parse::<u64>().map_err(|_| ParseError {
+        line: 1,
+        column: column_of(line, offset),
+        text: s.to_string(),
+        kind: ParseErrorKind::InvalidNumber,
+    })
+}
+
+#[cfg(test)]
+mod test_category_graph {
+    use super::*;
+
+    fn maps(names: &[&str]) -> Vec<Maps> {
+        names
+            .iter()
+            .map(|name| Maps::init(&format!("{} map:", name)).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_route() {
+        let graph = CategoryGraph::new(&maps(&["a-to-b", "b-to-c", "c-to-d"])).unwrap();
+        let forward = |map| Step {
+            map,
+            inverse: false,
+        };
+        let inverse = |map| Step { map, inverse: true };
+
+        assert_eq!(
+            graph.route("a", "d"),
+            Ok(vec![forward(0), forward(1), forward(2)])
+        );
+        assert_eq!(graph.route("b", "c"), Ok(vec![forward(1)]));
+        assert_eq!(graph.route("c", "a"), Ok(vec![inverse(1), inverse(0)]));
+    }
+
+    #[test]
+    fn test_unreachable() {
+        let graph = CategoryGraph::new(&maps(&["a-to-b", "c-to-d"])).unwrap();
+        assert_eq!(
+            graph.route("a", "d"),
+            Err(RouteError::Unreachable {
+                from: "a".to_string(),
+                to: "d".to_string()
+            })
+        );
+        assert_eq!(
+            graph.route("a", "x"),
+            Err(RouteError::UnknownCategory("x".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_cycle() {
+        let result = CategoryGraph::new(&maps(&["a-to-b", "b-to-c", "c-to-a"]));
+        assert_eq!(
+            result.err(),
+            Some(RouteError::Cycle(vec![
+                "a".to_string(),
+                "b".to_string(),
+                "c".to_string(),
+                "a".to_string()
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_duplicate_edge() {
+        let result = CategoryGraph::new(&maps(&["a-to-b", "b-to-c", "a-to-b"]));
+        assert_eq!(
+            result.err(),
+            Some(RouteError::DuplicateEdge {
+                src: "a".to_string(),
+                dest: "b".to_string()
+            })
+        );
+    }
 }
 
 #[cfg(test)]
@@ -63,16 +958,19 @@ mod test_maps {
 
     #[test]
     fn test_init() {
-        let maps = Maps::init("seed-to-soil maps: \n");
+        let maps = Maps::init("seed-to-soil maps: \n").unwrap();
         assert_eq!(maps.src_name, "seed");
         assert_eq!(maps.dest_name, "soil");
     }
 
     #[test]
     fn test_add_element() {
-        let mut maps = Maps::init("seed-to-soil maps: \n");
-        maps.add_element("1 2 3\n");
-        maps.add_element("4 5 6");
+        let layout = ColumnLayout::parse("src,dest,range").unwrap();
+        let mut maps = Maps::init("seed-to-soil maps: \n")
+            .unwrap()
+            .with_layout(layout);
+        maps.add_element("1 2 3\n").unwrap();
+        maps.add_element("4 5 6").unwrap();
 
         assert_eq!(maps.elements.len(), 2);
         assert_eq!(
@@ -85,11 +983,194 @@ mod test_maps {
         );
     }
 
+    #[test]
+    fn test_init_error() {
+        let err = Maps::init("seed-soil map:").err().unwrap();
+        assert_eq!(err.kind, ParseErrorKind::InvalidHeader);
+        assert_eq!(err.column, 1);
+        assert_eq!(err.text, "seed-soil");
+
+        let err = Maps::init("  seed-to-soil").err().unwrap();
+        assert_eq!(err.kind, ParseErrorKind::InvalidHeader);
+        assert_eq!(err.column, 3);
+
+        assert!(Maps::init("").is_err());
+    }
+
+    #[test]
+    fn test_add_element_error() {
+        let mut maps = Maps::init("a-to-b map:").unwrap();
+
+        let err = maps.add_element("1 2").unwrap_err();
+        assert_eq!(
+            err.kind,
+            ParseErrorKind::MissingColumn(ColumnLayout::PUZZLE)
+        );
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 4: expected `<dest> <src> <range>` [1 2]"
+        );
+        assert_eq!(err.column, 4);
+
+        let err = maps.add_element("1 2 3 4").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::ExtraColumn);
+        assert_eq!((err.column, err.text.as_str()), (7, "4"));
+
+        let err = maps.add_element("1  x2 3").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidNumber);
+        assert_eq!((err.column, err.text.as_str()), (4, "x2"));
+
+        assert!(maps.elements.is_empty());
+    }
+
+    #[test]
+    fn test_add_element_with_layout() {
+        // the puzzle lists the destination first
+        let mut maps = Maps::init("a-to-b map:").unwrap();
+        maps.add_element("1 2 3").unwrap();
+        assert_eq!(
+            maps.elements[0],
+            MapElement {
+                dest: 1,
+                src: 2,
+                range: 3,
+            }
+        );
+
+        let layout = ColumnLayout::parse("src,range,dest").unwrap();
+        let mut maps = Maps::init("a-to-b map:").unwrap().with_layout(layout);
+        maps.add_element("1 2 3").unwrap();
+        assert_eq!(
+            maps.elements[0],
+            MapElement {
+                dest: 3,
+                src: 1,
+                range: 2,
+            }
+        );
+
+        let err = maps.add_element("1").unwrap_err();
+        assert_eq!(err.kind.to_string(), "expected `<src> <range> <dest>`");
+    }
+
+    #[test]
+    fn test_column_layout_parse() {
+        assert_eq!(
+            ColumnLayout::parse("dest, src, range"),
+            Some(ColumnLayout::PUZZLE)
+        );
+        assert_eq!(ColumnLayout::parse("dest,src"), None);
+        assert_eq!(ColumnLayout::parse("dest,src,src"), None);
+        assert_eq!(ColumnLayout::parse("dest,src,range,range"), None);
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let almanac = Almanac::new(SAMPLE).unwrap();
+        for maps in &almanac.maps {
+            let printed = maps.to_string();
+            let mut lines = printed.lines();
+            let mut parsed = Maps::init(lines.next().unwrap()).unwrap();
+            for line in lines {
+                parsed.add_element(line).unwrap();
+            }
+            assert_eq!(&parsed, maps);
+            assert_eq!(parsed.to_string(), printed);
+        }
+
+        // rows are written back sorted by source, in the layout they were read
+        let layout = ColumnLayout::parse("range,src,dest").unwrap();
+        let mut maps = Maps::init("seed-to-soil map:").unwrap().with_layout(layout);
+        maps.add_element("2 98 50").unwrap();
+        maps.add_element("48 50 52").unwrap();
+        assert_eq!(maps.to_string(), "seed-to-soil map:\n48 50 52\n2 98 50\n");
+    }
+
+    #[test]
+    fn test_add_element_sorted() {
+        let mut maps = Maps::init("a-to-b map:").unwrap();
+        maps.add_element("0 30 5").unwrap();
+        maps.add_element("0 10 5").unwrap();
+        maps.add_element("0 20 10").unwrap();
+        maps.add_element("0 15 5").unwrap();
+
+        let srcs = maps.elements.iter().map(|e| e.src).collect::<Vec<_>>();
+        assert_eq!(srcs, vec![10, 15, 20, 30]);
+    }
+
+    #[test]
+    fn test_add_element_overlap() {
+        let mut maps = Maps::init("a-to-b map:").unwrap();
+        maps.add_element("100 10 10").unwrap();
+        maps.add_element("200 30 10").unwrap();
+
+        let err = maps.add_element("300 15 10").unwrap_err().kind;
+        assert_eq!(
+            err,
+            ParseErrorKind::Element(ElementError::Overlap(
+                MapElement {
+                    dest: 100,
+                    src: 10,
+                    range: 10
+                },
+                MapElement {
+                    dest: 300,
+                    src: 15,
+                    range: 10
+                }
+            ))
+        );
+        assert_eq!(
+            err.to_string(),
+            "source range of [300 15 10] overlaps with [100 10 10]"
+        );
+
+        assert!(maps.add_element("300 25 10").is_err());
+        assert!(maps.add_element("300 0 100").is_err());
+        assert!(maps.add_element("300 20 10").is_ok());
+        assert_eq!(maps.elements.len(), 3);
+    }
+
+    #[test]
+    fn test_add_element_empty() {
+        let mut maps = Maps::init("a-to-b map:").unwrap();
+        maps.add_element("100 10 10").unwrap();
+
+        let err = maps.add_element("5 10 0").unwrap_err();
+        assert_eq!(err.kind.to_string(), "[5 10 0] maps an empty range");
+        assert_eq!(maps.elements.len(), 1);
+        assert_eq!(maps.get_dest(10), 100);
+
+        let mut maps = Maps::init("a-to-b map:").unwrap();
+        assert!(maps.add_element("5 10 0").is_err());
+        maps.add_element("100 10 10").unwrap();
+        assert_eq!(maps.get_dest(10), 100);
+    }
+
+    #[test]
+    fn test_add_element_overflow() {
+        let mut maps = Maps::init("a-to-b map:").unwrap();
+        assert!(matches!(
+            maps.add_element("0 18446744073709551615 2")
+                .unwrap_err()
+                .kind,
+            ParseErrorKind::Element(ElementError::Overflow(_))
+        ));
+        assert!(matches!(
+            maps.add_element("18446744073709551615 0 2")
+                .unwrap_err()
+                .kind,
+            ParseErrorKind::Element(ElementError::Overflow(_))
+        ));
+        assert!(maps.elements.is_empty());
+    }
+
     #[test]
     fn test_get_dest() {
         let maps = Maps {
             src_name: "src".to_string(),
             dest_name: "dest".to_string(),
+            layout: ColumnLayout::default(),
             elements: vec![
                 MapElement {
                     dest: 1,
@@ -116,4 +1197,441 @@ mod test_maps {
         assert_eq!(maps.get_dest(30), 40);
         assert_eq!(maps.get_dest(31), 31);
     }
+
+    #[test]
+    fn test_compose() {
+        let mut first = Maps::init("a-to-b map:").unwrap();
+        first.add_element("100 10 10").unwrap();
+        first.add_element("10 100 10").unwrap();
+        let mut second = Maps::init("b-to-c map:").unwrap();
+        second.add_element("50 105 10").unwrap();
+
+        let composed = first.compose(&second);
+        assert_eq!(composed.src_name, "a");
+        assert_eq!(composed.dest_name, "c");
+        for v in 0..200 {
+            assert_eq!(composed.get_dest(v), second.get_dest(first.get_dest(v)));
+        }
+
+        // adjacent pieces are merged, identity pieces are left out
+        assert_eq!(
+            composed.to_string(),
+            "a-to-c map:\n100 10 5\n50 15 5\n10 100 10\n55 110 5\n"
+        );
+    }
+
+    #[test]
+    fn test_invert() {
+        let mut maps = Maps::init("a-to-b map:").unwrap();
+        maps.add_element("20 10 5").unwrap();
+        maps.add_element("10 20 5").unwrap();
+        maps.add_element("15 15 5").unwrap();
+
+        let inverse = maps.invert();
+        assert_eq!(inverse.src_name, "b");
+        assert_eq!(inverse.dest_name, "a");
+        for v in 0..50 {
+            assert_eq!(inverse.get_dest(maps.get_dest(v)), v);
+        }
+    }
+
+    #[test]
+    fn test_get_srcs() {
+        let mut maps = Maps::init("a-to-b map:").unwrap();
+        maps.add_element("30 10 5").unwrap();
+
+        // mapped from the element, and as is from the gap
+        assert_eq!(maps.get_srcs(32), vec![12, 32]);
+
+        // covered by the element, so nothing is mapped to it
+        assert!(maps.get_srcs(12).is_empty());
+
+        assert_eq!(maps.get_srcs(5), vec![5]);
+    }
+
+    #[test]
+    fn test_get_dest_ranges() {
+        let maps = Maps {
+            src_name: "src".to_string(),
+            dest_name: "dest".to_string(),
+            layout: ColumnLayout::default(),
+            elements: vec![
+                MapElement {
+                    dest: 100,
+                    src: 10,
+                    range: 10,
+                },
+                MapElement {
+                    dest: 200,
+                    src: 30,
+                    range: 5,
+                },
+            ],
+        };
+
+        // inside of a single element, and not covered at all
+        assert_eq!(maps.get_dest_ranges(&[12..15, 0..5]), vec![102..105, 0..5]);
+
+        // split at element boundaries, gaps pass through, empty ranges are dropped
+        let mut ranges = maps.get_dest_ranges(&[5..40, 7..7]);
+        ranges.sort_by_key(|r| r.start);
+        assert_eq!(ranges, vec![5..10, 20..30, 35..40, 100..110, 200..205]);
+    }
+}
+
+#[cfg(test)]
+mod test_almanac {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let almanac = Almanac::new(SAMPLE).unwrap();
+        assert_eq!(almanac.seeds, vec![79, 14, 55, 13]);
+        assert_eq!(almanac.maps.len(), 7);
+        assert_eq!(almanac.maps[0].src_name, "seed");
+        assert_eq!(almanac.maps[6].dest_name, "location");
+        assert_eq!(almanac.maps[6].elements.len(), 2);
+    }
+
+    #[test]
+    fn test_new_error() {
+        let err = Almanac::new("seed: 1 2").err().unwrap();
+        assert_eq!((err.line, err.kind), (1, ParseErrorKind::MissingSeeds));
+
+        let err = Almanac::new("seeds: 1 -2").err().unwrap();
+        assert_eq!((err.line, err.column), (1, 10));
+        assert_eq!(err.kind, ParseErrorKind::InvalidNumber);
+
+        let err = Almanac::new("seeds: 1\n\n1 2 3").err().unwrap();
+        assert_eq!(
+            (err.line, err.kind),
+            (3, ParseErrorKind::ElementBeforeHeader)
+        );
+
+        let input = "seeds: 1\n\na-to-b map:\n1 2 3\n\nc-to-d map:\n";
+        let err = Almanac::new(input).err().unwrap();
+        assert_eq!(err.line, 1);
+        assert_eq!(
+            err.kind,
+            ParseErrorKind::Route(RouteError::UnknownCategory("seed".to_string()))
+        );
+
+        let input =
+            "seeds: 1\n\nseed-to-soil map:\n\nsoil-to-location map:\n\n seed-to-soil map:\n";
+        let err = Almanac::new(input).err().unwrap();
+        assert_eq!((err.line, err.column), (7, 2));
+        assert!(matches!(
+            err.kind,
+            ParseErrorKind::Route(RouteError::DuplicateEdge { .. })
+        ));
+
+        let input = "seeds: 1\n\nseed-to-location map:\n\nlocation-to-seed map:\n";
+        let err = Almanac::new(input).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "line 3, column 1: maps form a cycle: location -> seed -> location [seed-to-location map:]"
+        );
+
+        let input = "seeds: 1\n\na-to-b map:\n1 2 3\n1 2 y\n";
+        let err = Almanac::new(input).err().unwrap();
+        assert_eq!(err.to_string(), "line 5, column 5: invalid number [y]");
+    }
+
+    #[test]
+    fn test_into_error() {
+        let input = "seeds: 1 2\n\na-to-b map:\n1 2 3\n1 2 yy\n";
+        let err = Almanac::new(input).err().unwrap().into_error(input);
+        assert_eq!(
+            err.to_string(),
+            "line 5, column 5: expected a number, found [yy]"
+        );
+        assert_eq!((err.text.as_str(), err.width), ("1 2 yy", 2));
+
+        let input = "seeds: 1 2\n\na-to-b map:\n1 2\n";
+        let err = Almanac::new(input).err().unwrap().into_error(input);
+        assert_eq!(
+            err.to_string(),
+            "line 4, column 4: expected `<dest> <src> <range>`, found end of line"
+        );
+
+        let input = "seeds: 1 2\n\nb-to-c map:\n\na-to-b map:\n\nb-to-c map:\n";
+        let err = Almanac::new(input).err().unwrap().into_error(input);
+        assert_eq!(
+            err.to_string(),
+            "line 7, column 1: more than one b-to-c map"
+        );
+        assert_eq!(err.width, 11);
+    }
+
+    #[test]
+    fn test_shuffled_maps() {
+        // sections in any order route the same way
+        let (seeds, sections) = SAMPLE.split_once("\n\n").unwrap();
+        let mut sections = sections.split("\n\n").collect::<Vec<_>>();
+        sections.reverse();
+        sections.swap(1, 4);
+        let shuffled = format!("{}\n\n{}", seeds, sections.join("\n\n"));
+
+        let almanac = Almanac::new(&shuffled).unwrap();
+        assert_eq!(almanac.maps[0].src_name, "humidity");
+        assert_eq!(part1::solve(&shuffled), Ok(35));
+        assert_eq!(part2::solve(&shuffled), Ok(46));
+        assert_eq!(almanac.get_seeds(82), vec![79]);
+        assert_eq!(almanac.convert("soil", "humidity", 81), Ok(78));
+        assert_eq!(almanac.compose_all().dest_name, "location");
+
+        // and maps may be walked backwards to get there
+        let input = "seeds: 79 1\n\nseed-to-soil map:\n50 79 1\n\nlocation-to-soil map:\n50 10 1\n";
+        let almanac = Almanac::new(input).unwrap();
+        assert_eq!(almanac.get_location(79), 10);
+        assert_eq!(almanac.get_seeds(10), vec![79, 50, 10]);
+    }
+
+    #[test]
+    fn test_compose_all() {
+        let almanac = Almanac::new(SAMPLE).unwrap();
+        let composed = almanac.compose_all();
+        assert_eq!(composed.src_name, "seed");
+        assert_eq!(composed.dest_name, "location");
+        for seed in 0..200 {
+            assert_eq!(composed.get_dest(seed), almanac.get_location(seed));
+        }
+
+        let almanac = Almanac::new("seeds: 1 2\n").unwrap();
+        let composed = almanac.compose_all();
+        assert_eq!(composed.to_string(), "seed-to-seed map:\n");
+        assert_eq!(composed.get_dest(7), 7);
+    }
+
+    #[test]
+    fn test_get_seeds() {
+        let almanac = Almanac::new(SAMPLE).unwrap();
+        assert_eq!(almanac.get_seeds(82), vec![79]);
+        assert_eq!(almanac.get_seeds(35), vec![13]);
+        for seed in 0..200 {
+            assert!(almanac
+                .get_seeds(almanac.get_location(seed))
+                .contains(&seed));
+        }
+    }
+
+    #[test]
+    fn test_find_lowest_location() {
+        let almanac = Almanac::new(SAMPLE).unwrap();
+        assert_eq!(almanac.find_lowest_location(&[79..93, 55..68]), Some(46));
+        assert_eq!(almanac.find_lowest_location(&[]), None);
+        assert_eq!(almanac.find_lowest_location(&[5..5, 7..7]), None);
+
+        // same as the lowest of the forward walk, also for ranges through every map
+        let ranges: [&[Range<u64>]; 3] =
+            [&[0..1, 2..2], &[13..14, 98..100], &[0..u64::MAX - 1, 3..4]];
+        for ranges in ranges {
+            let forward = almanac
+                .get_location_ranges(ranges)
+                .iter()
+                .map(|r| r.start)
+                .min();
+            assert_eq!(almanac.find_lowest_location(ranges), forward);
+        }
+    }
+
+    #[test]
+    fn test_convert() {
+        let almanac = Almanac::new(SAMPLE).unwrap();
+        assert_eq!(almanac.convert("seed", "location", 79), Ok(82));
+        assert_eq!(almanac.convert("seed", "soil", 79), Ok(81));
+        assert_eq!(almanac.convert("soil", "humidity", 81), Ok(78));
+        assert_eq!(almanac.convert("location", "seed", 82), Ok(79));
+        assert_eq!(almanac.convert("light", "seed", 74), Ok(79));
+        assert_eq!(almanac.convert("seed", "seed", 79), Ok(79));
+        assert_eq!(
+            almanac.convert("seed", "planet", 79),
+            Err(RouteError::UnknownCategory("planet".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_get_location() {
+        let almanac = Almanac::new(SAMPLE).unwrap();
+        assert_eq!(almanac.get_location(79), 82);
+        assert_eq!(almanac.get_location(14), 43);
+        assert_eq!(almanac.get_location(55), 86);
+        assert_eq!(almanac.get_location(13), 35);
+    }
+}
+
+#[cfg(test)]
+const SAMPLE: &str = r#"seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4"#;
+
+pub mod part1 {
+    use super::*;
+
+    pub fn solve(input: &str) -> Result<u64, Error> {
+        let almanac = Almanac::new(input).map_err(|e| e.into_error(input))?;
+        solve_almanac(&almanac)
+    }
+
+    pub fn solve_almanac(almanac: &Almanac) -> Result<u64, Error> {
+        almanac
+            .seeds
+            .iter()
+            .map(|&seed| almanac.get_location(seed))
+            .min()
+            .ok_or_else(no_seeds)
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        #[test]
+        fn test_solve_sample() {
+            assert_eq!(solve(SAMPLE), Ok(35));
+        }
+
+        #[test]
+        fn test_no_seeds() {
+            let err = solve("seeds:\n").unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "line 1, column 7: expected a seed, found end of line"
+            );
+            assert_eq!(super::super::part2::solve("seeds:"), Err(err));
+        }
+
+        #[test]
+        fn test_solve() {
+            let Some(input) = crate::input::test_input(5) else {
+                return;
+            };
+            assert_eq!(solve(&input), Ok(199602917));
+        }
+    }
+}
+
+pub mod part2 {
+    use super::*;
+
+    /// the seeds read as `<start> <length>` pairs, errors located in the `seeds:` line
+    fn seed_ranges(almanac: &Almanac) -> Result<Vec<Range<u64>>, Error> {
+        let line = almanac.seeds_line.as_str();
+        let spans = &almanac.seed_spans;
+        if almanac.seeds.len() % 2 == 1 {
+            let last = spans[spans.len() - 1].clone();
+            return Err(Error::invalid(
+                line,
+                &line[last],
+                "odd number of seeds, expected `<start> <length>` pairs",
+            ));
+        }
+
+        let mut ranges = Vec::new();
+        for (idx, pair) in almanac.seeds.chunks_exact(2).enumerate() {
+            let Some(end) = pair[0].checked_add(pair[1]) else {
+                let text = &line[spans[2 * idx].start..spans[2 * idx + 1].end];
+                return Err(Error::invalid(line, text, "seed range overflows u64"));
+            };
+            ranges.push(pair[0]..end);
+        }
+        Ok(ranges)
+    }
+
+    pub fn solve(input: &str) -> Result<u64, Error> {
+        let almanac = Almanac::new(input).map_err(|e| e.into_error(input))?;
+        solve_almanac(&almanac)
+    }
+
+    pub fn solve_almanac(almanac: &Almanac) -> Result<u64, Error> {
+        almanac
+            .get_location_ranges(&seed_ranges(almanac)?)
+            .iter()
+            .map(|r| r.start)
+            .min()
+            .ok_or_else(no_seeds)
+    }
+
+    /// same as `solve`, but searches from the lowest location back to the seeds
+    pub fn solve_reverse(input: &str) -> Result<u64, Error> {
+        let almanac = Almanac::new(input).map_err(|e| e.into_error(input))?;
+        solve_reverse_almanac(&almanac)
+    }
+
+    pub fn solve_reverse_almanac(almanac: &Almanac) -> Result<u64, Error> {
+        almanac
+            .find_lowest_location(&seed_ranges(almanac)?)
+            .ok_or_else(no_seeds)
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        #[test]
+        fn test_solve_sample() {
+            assert_eq!(solve(SAMPLE), Ok(46));
+            assert_eq!(solve_reverse(SAMPLE), Ok(46));
+        }
+
+        #[test]
+        fn test_seed_pairs() {
+            // fine for part 1, which has no pairs
+            let input = "seeds: 79 14 55\n\nseed-to-location map:\n50 98 2";
+            assert_eq!(super::super::part1::solve(input), Ok(14));
+
+            let err = solve(input).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "line 1, column 14: odd number of seeds, expected `<start> <length>` pairs"
+            );
+            assert_eq!((err.width, err.text.as_str()), (2, "seeds: 79 14 55"));
+            assert_eq!(solve_reverse(input), Err(err));
+
+            let err = solve("seeds: 1 2 18446744073709551615 5").unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "line 1, column 12: seed range overflows u64"
+            );
+            assert_eq!(err.width, 22);
+        }
+
+        #[test]
+        fn test_solve() {
+            let Some(input) = crate::input::test_input(5) else {
+                return;
+            };
+            assert_eq!(solve(&input), Ok(2254686));
+        }
+    }
 }
@@ -0,0 +1,232 @@
+//! the error of every parser: what was wrong, and where in the input
+
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ErrorKind {
+    /// `None` found means the line ended first
+    Unexpected {
+        expected: String,
+        found: Option<String>,
+    },
+    /// well formed, but not something the puzzle allows
+    Invalid(String),
+    /// a scaffolded part that isn't solved yet, not about any place in the input
+    NotImplemented(String),
+}
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorKind::Unexpected {
+                expected,
+                found: Some(found),
+            } => write!(f, "expected {}, found [{}]", expected, found),
+            ErrorKind::Unexpected {
+                expected,
+                found: None,
+            } => write!(f, "expected {}, found end of line", expected),
+            ErrorKind::Invalid(reason) => write!(f, "{}", reason),
+            ErrorKind::NotImplemented(what) => write!(f, "not implemented yet: {}", what),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Error {
+    /// known once the error leaves its solution
+    pub day: Option<u32>,
+    /// 1-based
+    pub line: usize,
+    /// 1-based, in chars. 0 if the error couldn't be placed in `text`
+    pub column: usize,
+    /// the whole line
+    pub text: String,
+    /// how many chars the error spans from `column`
+    pub width: usize,
+    pub kind: ErrorKind,
+}
+impl Error {
+    /// an unlocated error if `offset` is `None`
+    fn new(line: &str, offset: Option<usize>, width: usize, kind: ErrorKind) -> Self {
+        Error {
+            day: None,
+            line: 1,
+            column: offset.map_or(0, |offset| line[..offset].chars().count() + 1),
+            text: line.to_string(),
+            width: width.max(1),
+            kind,
+        }
+    }
+
+    /// `found`, a slice of `line`, is not `expected`
+    pub fn unexpected(line: &str, found: &str, expected: impl Into<String>) -> Self {
+        let kind = ErrorKind::Unexpected {
+            expected: expected.into(),
+            found: Some(found.to_string()),
+        };
+        Self::new(line, offset_of(line, found), found.chars().count(), kind)
+    }
+
+    /// `line` ended right after `after`, a slice of it, where `expected` should follow
+    pub fn missing(line: &str, after: &str, expected: impl Into<String>) -> Self {
+        let kind = ErrorKind::Unexpected {
+            expected: expected.into(),
+            found: None,
+        };
+        let offset = offset_of(line, after).map(|offset| offset + after.len());
+        Self::new(line, offset, 1, kind)
+    }
+
+    /// `at`, a slice of `line`, is wrong for `reason`
+    pub fn invalid(line: &str, at: &str, reason: impl Into<String>) -> Self {
+        let kind = ErrorKind::Invalid(reason.into());
+        Self::new(line, offset_of(line, at), at.chars().count(), kind)
+    }
+
+    /// `what` is not solved yet
+    pub fn not_implemented(what: impl Into<String>) -> Self {
+        Self::new("", Some(0), 1, ErrorKind::NotImplemented(what.into()))
+    }
+
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.line = line;
+        self
+    }
+
+    pub fn in_day(mut self, day: u32) -> Self {
+        self.day = Some(day);
+        self
+    }
+
+    /// moves an error found in `part`, a slice of `line`, to `line`
+    pub fn within(mut self, line: &str, part: &str) -> Self {
+        match offset_of(line, part) {
+            Some(offset) if self.column > 0 => self.column += line[..offset].chars().count(),
+            _ => self.column = 0,
+        }
+        self.text = line.to_string();
+        self
+    }
+
+    /// the message, followed by the line with a caret under the error
+    pub fn diagnostic(&self) -> String {
+        // nothing to point at
+        if self.column == 0 || matches!(self.kind, ErrorKind::NotImplemented(_)) {
+            return self.to_string();
+        }
+        let line_no = self.line.to_string();
+        let gutter = " ".repeat(line_no.len());
+        format!(
+            "{}\n{} |\n{} | {}\n{} | {}{}",
+            self,
+            gutter,
+            line_no,
+            self.text,
+            gutter,
+            " ".repeat(self.column - 1),
+            "^".repeat(self.width)
+        )
+    }
+}
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "day {}, ", day)?;
+        }
+        if let ErrorKind::NotImplemented(_) = self.kind {
+            return write!(f, "{}", self.kind);
+        }
+        if self.column == 0 {
+            return write!(f, "line {}: {}", self.line, self.kind);
+        }
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.kind
+        )
+    }
+}
+impl std::error::Error for Error {}
+
+/// byte offset of `part` in `line`, `None` if it isn't a slice of it
+fn offset_of(line: &str, part: &str) -> Option<usize> {
+    let offset = (part.as_ptr() as usize).wrapping_sub(line.as_ptr() as usize);
+    let inside = offset <= line.len() && part.len() <= line.len() - offset;
+    (inside && line.is_char_boundary(offset)).then_some(offset)
+}
+
+/// the value, or exits with the diagnostic of the error, for use in the `dayNN` binaries
+pub fn or_exit<T>(result: Result<T, Error>) -> T {
+    result.unwrap_or_else(|e| {
+        eprintln!("error: {}", e.diagnostic());
+        std::process::exit(1);
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_unexpected() {
+        let line = "Game 3: 8 purple";
+        let err = Error::unexpected(line, &line[10..], "red, green or blue").at_line(3);
+        assert_eq!(
+            err.to_string(),
+            "line 3, column 11: expected red, green or blue, found [purple]"
+        );
+        assert_eq!(
+            err.in_day(2).diagnostic(),
+            "day 2, line 3, column 11: expected red, green or blue, found [purple]
+  |
+3 | Game 3: 8 purple
+  |           ^^^^^^"
+        );
+    }
+
+    #[test]
+    fn test_missing() {
+        let line = "Card 1: 1 2";
+        let err = Error::missing(line, line, "`|`");
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 12: expected `|`, found end of line"
+        );
+        assert!(err
+            .diagnostic()
+            .ends_with("\n1 | Card 1: 1 2\n  |            ^"));
+    }
+
+    #[test]
+    fn test_within() {
+        let line = "é: 1 x";
+        let part = &line[4..];
+        let err = Error::unexpected(part, &part[2..], "a number").within(line, part);
+        assert_eq!((err.column, err.width), (6, 1));
+        assert_eq!(err.text, line);
+
+        let err = Error::invalid(line, &line[4..], "too long");
+        assert_eq!(err.to_string(), "line 1, column 4: too long");
+    }
+
+    #[test]
+    fn test_not_implemented() {
+        let err = Error::not_implemented("part 1").in_day(6);
+        assert_eq!(err.to_string(), "day 6, not implemented yet: part 1");
+        // no line to point at
+        assert_eq!(err.diagnostic(), err.to_string());
+    }
+
+    #[test]
+    fn test_not_a_slice() {
+        let elsewhere = String::from("b");
+        let err = Error::unexpected("abc", &elsewhere, "a").at_line(2);
+        assert_eq!(err.column, 0);
+        assert_eq!(err.to_string(), "line 2: expected a, found [b]");
+        assert_eq!(err.diagnostic(), err.to_string());
+
+        let line = "x: 1 b";
+        let err = Error::unexpected(line, &line[5..], "a number").within("elsewhere", line);
+        assert_eq!((err.column, err.text.as_str()), (0, "elsewhere"));
+    }
+}
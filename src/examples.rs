@@ -0,0 +1,264 @@
+//! examples taken from saved puzzle descriptions, and the answers expected for them.
+//!
+//! the examples of day NN are saved as `examples/dayNN-K.txt`, and what each of them should give
+//! is listed in `examples/expected.txt`, one `<file> <part> <answer>` per line.
+
+use std::path::{Path, PathBuf};
+
+use crate::html;
+
+pub const EXAMPLES_DIR: &str = "examples";
+pub const EXPECTED_FILE: &str = "expected.txt";
+
+/// an example input, and the answers of the parts it is given for
+#[derive(Debug, PartialEq)]
+pub struct Example {
+    pub input: String,
+    pub answers: Vec<(u32, String)>,
+}
+
+/// the examples of a puzzle description, one `<article>` per part.
+/// each part is taken to use the first `<pre><code>` block of its article, or the example
+/// of the part before if it has none, and to expect the last emphasized `<code>` of its article.
+pub fn extract(page: &str) -> Vec<Example> {
+    let mut examples: Vec<Example> = Vec::new();
+    for (idx, article) in html::elements(page, "article").into_iter().enumerate() {
+        let part = idx as u32 + 1;
+        let answer = html::elements(article, "code")
+            .into_iter()
+            .rev()
+            .find_map(|code| {
+                let em = code.trim().strip_prefix("<em>")?.strip_suffix("</em>")?;
+                Some(html::text(em))
+            });
+
+        let input = html::code_blocks(article).into_iter().next();
+        let example = match input {
+            Some(input) if !examples.iter().any(|e| e.input == input) => {
+                examples.push(Example {
+                    input,
+                    answers: Vec::new(),
+                });
+                examples.last_mut().unwrap()
+            }
+            Some(input) => examples.iter_mut().find(|e| e.input == input).unwrap(),
+            None => match examples.last_mut() {
+                Some(example) => example,
+                None => continue,
+            },
+        };
+        if let Some(answer) = answer {
+            example.answers.push((part, answer));
+        }
+    }
+    examples
+}
+
+/// `dayNN-K.txt`, `index` counting from 1
+pub fn file_name(day: u32, index: usize) -> String {
+    format!("day{:02}-{}.txt", day, index)
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Expectation {
+    /// relative to the examples directory
+    pub file: String,
+    pub part: u32,
+    pub answer: String,
+}
+impl Expectation {
+    /// the day of `dayNN-K.txt`
+    pub fn day(&self) -> Option<u32> {
+        self.file.strip_prefix("day")?.get(..2)?.parse().ok()
+    }
+}
+
+pub fn format_expectations(expectations: &[Expectation]) -> String {
+    let mut formatted = "# file part answer\n".to_string();
+    for e in expectations {
+        formatted += &format!("{} {} {}\n", e.file, e.part, e.answer);
+    }
+    formatted
+}
+
+pub fn parse_expectations(input: &str) -> Result<Vec<Expectation>, String> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|(idx, line)| {
+            let mut fields = line.trim().splitn(3, ' ');
+            let expectation = (|| {
+                Some(Expectation {
+                    file: fields.next()?.to_string(),
+                    part: fields.next()?.parse().ok()?,
+                    answer: fields.next()?.trim().to_string(),
+                })
+            })();
+            expectation.filter(|e| e.day().is_some()).ok_or_else(|| {
+                format!(
+                    "line {}: expected `dayNN-K.txt <part> <answer>` [{}]",
+                    idx + 1,
+                    line
+                )
+            })
+        })
+        .collect()
+}
+
+/// `examples/` in the crate root
+pub fn default_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join(EXAMPLES_DIR)
+}
+
+/// the expectations in `dir`, none if there is no expectations file
+pub fn load_expectations(dir: &Path) -> Result<Vec<Expectation>, String> {
+    let path = dir.join(EXPECTED_FILE);
+    match std::fs::read_to_string(&path) {
+        Ok(expected) => parse_expectations(&expected),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("can't read [{}]: {}", path.display(), e)),
+    }
+}
+
+/// writes the examples of `day` to `dir`, replacing the ones saved before,
+/// and returns the files written
+pub fn save(dir: &Path, day: u32, examples: &[Example]) -> Result<Vec<PathBuf>, String> {
+    let io_error = |path: &Path, e: std::io::Error| format!("[{}]: {}", path.display(), e);
+    std::fs::create_dir_all(dir).map_err(|e| io_error(dir, e))?;
+
+    let mut expectations = load_expectations(dir)?
+        .into_iter()
+        .filter(|e| e.day() != Some(day))
+        .collect::<Vec<_>>();
+    // stale examples of the day would no longer be listed
+    let prefix = format!("day{:02}-", day);
+    for entry in std::fs::read_dir(dir).map_err(|e| io_error(dir, e))? {
+        let path = entry.map_err(|e| io_error(dir, e))?.path();
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        if name.starts_with(&prefix) && name.ends_with(".txt") {
+            std::fs::remove_file(&path).map_err(|e| io_error(&path, e))?;
+        }
+    }
+
+    let mut written = Vec::new();
+    for (idx, example) in examples.iter().enumerate() {
+        let file = file_name(day, idx + 1);
+        let path = dir.join(&file);
+        std::fs::write(&path, &example.input).map_err(|e| io_error(&path, e))?;
+        written.push(path);
+
+        expectations.extend(example.answers.iter().map(|(part, answer)| Expectation {
+            file: file.clone(),
+            part: *part,
+            answer: answer.clone(),
+        }));
+    }
+    expectations.sort_by(|a, b| (&a.file, a.part).cmp(&(&b.file, b.part)));
+
+    let path = dir.join(EXPECTED_FILE);
+    std::fs::write(&path, format_expectations(&expectations)).map_err(|e| io_error(&path, e))?;
+    written.push(path);
+    Ok(written)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const PAGE: &str = r#"<main>
+<article class="day-desc"><h2>--- Day 1: Trebuchet?! ---</h2>
+<p>For example:</p>
+<pre><code>1abc2
+treb7uchet
+</code></pre>
+<p>In this example, the calibration values are <code>12</code> and <code>77</code>.
+Adding these together produces <code><em>89</em></code>.</p>
+</article>
+<p>Your puzzle answer was <code>53651</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<pre><code>two1nine
+xtwone3four
+</code></pre>
+<p>Adding these together produces <code><em>53</em></code>.</p>
+</article>
+</main>"#;
+
+    #[test]
+    fn test_extract() {
+        assert_eq!(
+            extract(PAGE),
+            vec![
+                Example {
+                    input: "1abc2\ntreb7uchet\n".to_string(),
+                    answers: vec![(1, "89".to_string())],
+                },
+                Example {
+                    input: "two1nine\nxtwone3four\n".to_string(),
+                    answers: vec![(2, "53".to_string())],
+                },
+            ]
+        );
+
+        // part two asks something else about the same example
+        let page = "<article><pre><code>1 2</code></pre><code><em>3</em></code></article>\
+            <article><p>again</p><code><em>2</em></code></article>";
+        assert_eq!(
+            extract(page),
+            vec![Example {
+                input: "1 2".to_string(),
+                answers: vec![(1, "3".to_string()), (2, "2".to_string())],
+            }]
+        );
+        assert_eq!(extract("<p>no puzzle</p>"), vec![]);
+    }
+
+    #[test]
+    fn test_expectations() {
+        let expectations = vec![
+            Expectation {
+                file: "day01-1.txt".to_string(),
+                part: 1,
+                answer: "142".to_string(),
+            },
+            Expectation {
+                file: "day13-2.txt".to_string(),
+                part: 2,
+                answer: "two words".to_string(),
+            },
+        ];
+        let formatted = format_expectations(&expectations);
+        assert_eq!(
+            formatted,
+            "# file part answer\nday01-1.txt 1 142\nday13-2.txt 2 two words\n"
+        );
+        assert_eq!(parse_expectations(&formatted), Ok(expectations.clone()));
+        assert_eq!(expectations[1].day(), Some(13));
+
+        assert!(parse_expectations("day01-1.txt 1").is_err());
+        assert!(parse_expectations("sample.txt 1 142").is_err());
+    }
+
+    #[test]
+    fn test_save() {
+        let dir = crate::input::test_dir("examples");
+        std::fs::write(dir.join(file_name(1, 3)), "stale").unwrap();
+        std::fs::write(
+            dir.join(EXPECTED_FILE),
+            "day01-3.txt 1 0\nday02-1.txt 1 8\n",
+        )
+        .unwrap();
+
+        let written = save(&dir, 1, &extract(PAGE)).unwrap();
+        assert_eq!(written.len(), 3);
+        assert!(!dir.join(file_name(1, 3)).exists());
+        assert_eq!(
+            std::fs::read_to_string(dir.join(file_name(1, 2))).unwrap(),
+            "two1nine\nxtwone3four\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join(EXPECTED_FILE)).unwrap(),
+            "# file part answer\nday01-1.txt 1 89\nday01-2.txt 2 53\nday02-1.txt 1 8\n"
+        );
+    }
+}
@@ -0,0 +1,128 @@
+//! just enough html to read saved puzzle descriptions
+//! (which are well-formed and don't nest the tags we look for).
+
+/// the text inside the first `<tag ...>` .. `</tag>` found in `html`, and the rest after it
+fn element<'a>(html: &'a str, tag: &str) -> Option<(&'a str, &'a str)> {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+
+    let mut from = 0;
+    loop {
+        let start = from + html[from..].find(&open)?;
+        let after_name = &html[start + open.len()..];
+        // `<pre>` but not `<prefix>`
+        if after_name.starts_with(['>', ' ', '\t', '\n']) {
+            let content_start = start + open.len() + after_name.find('>')? + 1;
+            let end = content_start + html[content_start..].find(&close)?;
+            return Some((&html[content_start..end], &html[end + close.len()..]));
+        }
+        from = start + open.len();
+    }
+}
+
+/// every element `tag` in `html`, in order
+pub fn elements<'a>(mut html: &'a str, tag: &str) -> Vec<&'a str> {
+    let mut found = Vec::new();
+    while let Some((content, rest)) = element(html, tag) {
+        found.push(content);
+        html = rest;
+    }
+    found
+}
+
+/// removes the markup and decodes the entities
+pub fn text(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    decode_entities(&text)
+}
+
+fn decode_entities(s: &str) -> String {
+    let mut decoded = String::new();
+    let mut rest = s;
+    while let Some(idx) = rest.find('&') {
+        decoded += &rest[..idx];
+        rest = &rest[idx..];
+
+        let entity = rest
+            .find(';')
+            .map(|end| (&rest[1..end], end))
+            .and_then(|(name, end)| {
+                let c = match name {
+                    "lt" => '<',
+                    "gt" => '>',
+                    "amp" => '&',
+                    "quot" => '"',
+                    "apos" => '\'',
+                    "nbsp" => ' ',
+                    _ => {
+                        let code = match name.strip_prefix("#x").or(name.strip_prefix("#X")) {
+                            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                            None => name.strip_prefix('#')?.parse::<u32>().ok()?,
+                        };
+                        char::from_u32(code)?
+                    }
+                };
+                Some((c, end))
+            });
+        match entity {
+            Some((c, end)) => {
+                decoded.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded + rest
+}
+
+/// the text of every `<pre><code>` block, as the examples of a puzzle are shown
+pub fn code_blocks(html: &str) -> Vec<String> {
+    elements(html, "pre")
+        .into_iter()
+        .filter_map(|pre| element(pre, "code"))
+        .map(|(code, _)| text(code))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_elements() {
+        let html = "<p>a</p><pref>x</pref><p class=\"b\">b</p><pre>c";
+        assert_eq!(elements(html, "p"), vec!["a", "b"]);
+        assert_eq!(elements(html, "pre"), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_text() {
+        assert_eq!(
+            text("<em>1 &lt; 2</em> &amp;&amp; &#x41;&#66; &unknown; & done"),
+            "1 < 2 && AB &unknown; & done"
+        );
+    }
+
+    #[test]
+    fn test_code_blocks() {
+        let html = r#"<article><p>For example:</p>
+<pre><code>1abc2
+<em>pqr3stu8vwx</em>
+</code></pre>
+<p>Here <code>12</code> is inline.</p>
+<pre><code>a&gt;b</code></pre></article>"#;
+        assert_eq!(code_blocks(html), vec!["1abc2\npqr3stu8vwx\n", "a>b"]);
+    }
+}
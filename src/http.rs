@@ -0,0 +1,344 @@
+//! a minimal HTTP/1.1 client over `TcpStream`, enough to talk to the puzzle server or a stand-in for it.
+//! only plain `http://` urls are supported, as TLS would need a dependency.
+
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug)]
+pub enum HttpError {
+    InvalidUrl(String),
+    /// an `https://` url, which would need TLS
+    TlsUnsupported(String),
+    Io(io::Error),
+    InvalidResponse(String),
+}
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HttpError::InvalidUrl(url) => write!(
+                f,
+                "unsupported url [{}], expected http://host[:port][/path]",
+                url
+            ),
+            HttpError::TlsUnsupported(url) => write!(
+                f,
+                "[{}] needs TLS, which this client doesn't support, use an http:// proxy instead",
+                url
+            ),
+            HttpError::Io(e) => write!(f, "{}", e),
+            HttpError::InvalidResponse(reason) => write!(f, "invalid response: {}", reason),
+        }
+    }
+}
+impl std::error::Error for HttpError {}
+impl From<io::Error> for HttpError {
+    fn from(e: io::Error) -> Self {
+        HttpError::Io(e)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Url {
+    pub host: String,
+    pub port: u16,
+    /// always starts with `/`
+    pub path: String,
+}
+impl Url {
+    pub fn parse(url: &str) -> Result<Self, HttpError> {
+        let invalid = || HttpError::InvalidUrl(url.to_string());
+        if url.starts_with("https://") {
+            return Err(HttpError::TlsUnsupported(url.to_string()));
+        }
+        let rest = url.strip_prefix("http://").ok_or_else(invalid)?;
+        let (authority, path) = match rest.find('/') {
+            Some(idx) => (&rest[..idx], &rest[idx..]),
+            None => (rest, "/"),
+        };
+        let (host, port) = match authority.split_once(':') {
+            Some((host, port)) => (host, port.parse::<u16>().map_err(|_| invalid())?),
+            None => (authority, 80),
+        };
+        if host.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(Url {
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+pub fn get(url: &str, headers: &[(&str, &str)]) -> Result<Response, HttpError> {
+    request("GET", url, headers, None)
+}
+
+/// posts `body` as `application/x-www-form-urlencoded`
+pub fn post_form(url: &str, headers: &[(&str, &str)], body: &str) -> Result<Response, HttpError> {
+    request("POST", url, headers, Some(body))
+}
+
+fn request(
+    method: &str,
+    url: &str,
+    headers: &[(&str, &str)],
+    body: Option<&str>,
+) -> Result<Response, HttpError> {
+    let url = Url::parse(url)?;
+    let mut stream = TcpStream::connect((url.host.as_str(), url.port))?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+
+    let mut request = format!(
+        "{} {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n",
+        method, url.path, url.host
+    );
+    for (name, value) in headers {
+        request += &format!("{}: {}\r\n", name, value);
+    }
+    if let Some(body) = body {
+        request += "Content-Type: application/x-www-form-urlencoded\r\n";
+        request += &format!("Content-Length: {}\r\n", body.len());
+    }
+    request += "\r\n";
+    request += body.unwrap_or("");
+    stream.write_all(request.as_bytes())?;
+
+    read_response(BufReader::new(stream))
+}
+
+fn read_response(mut reader: impl BufRead) -> Result<Response, HttpError> {
+    let invalid = |reason: &str| HttpError::InvalidResponse(reason.to_string());
+
+    let mut status_line = String::new();
+    reader.read_line(&mut status_line)?;
+    let status = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|s| s.parse::<u16>().ok())
+        .ok_or_else(|| invalid("no status code"))?;
+
+    let mut content_length = None;
+    let mut chunked = false;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(invalid("unexpected end of headers"));
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            let value = value.trim();
+            match name.trim().to_ascii_lowercase().as_str() {
+                "content-length" => {
+                    content_length = Some(
+                        value
+                            .parse::<usize>()
+                            .map_err(|_| invalid("content-length"))?,
+                    )
+                }
+                "transfer-encoding" => chunked = value.eq_ignore_ascii_case("chunked"),
+                _ => {}
+            }
+        }
+    }
+
+    let mut body = Vec::new();
+    if chunked {
+        loop {
+            let mut size_line = String::new();
+            reader.read_line(&mut size_line)?;
+            let size_str = size_line.trim().split(';').next().unwrap_or("");
+            let size = usize::from_str_radix(size_str, 16).map_err(|_| invalid("chunk size"))?;
+            if size == 0 {
+                break;
+            }
+            let mut chunk = vec![0; size];
+            reader.read_exact(&mut chunk)?;
+            body.extend(chunk);
+            let mut crlf = String::new();
+            reader.read_line(&mut crlf)?;
+        }
+    } else if let Some(len) = content_length {
+        body.resize(len, 0);
+        reader.read_exact(&mut body)?;
+    } else {
+        reader.read_to_end(&mut body)?;
+    }
+
+    Ok(Response {
+        status,
+        body: String::from_utf8(body).map_err(|_| invalid("body is not utf-8"))?,
+    })
+}
+
+/// a stand-in server for tests, answering each connection with the next canned response
+#[cfg(test)]
+pub(crate) mod mock {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    #[derive(Debug, Clone)]
+    pub struct Request {
+        pub method: String,
+        pub path: String,
+        pub headers: Vec<(String, String)>,
+        pub body: String,
+    }
+    impl Request {
+        pub fn header(&self, name: &str) -> Option<&str> {
+            self.headers
+                .iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.as_str())
+        }
+    }
+
+    pub struct Server {
+        pub url: String,
+        pub requests: Arc<Mutex<Vec<Request>>>,
+    }
+
+    /// serves `responses` as (status, body) in order, then stops accepting
+    pub fn serve(responses: Vec<(u16, &str)>) -> Server {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let recorded = requests.clone();
+        let responses = responses
+            .into_iter()
+            .map(|(status, body)| (status, body.to_string()))
+            .collect::<Vec<_>>();
+        thread::spawn(move || {
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut parts = request_line.split_whitespace();
+                let method = parts.next().unwrap_or("").to_string();
+                let path = parts.next().unwrap_or("").to_string();
+
+                let mut headers = Vec::new();
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    let line = line.trim_end();
+                    if line.is_empty() {
+                        break;
+                    }
+                    let (name, value) = line.split_once(':').unwrap();
+                    headers.push((name.trim().to_string(), value.trim().to_string()));
+                }
+
+                let len = headers
+                    .iter()
+                    .find(|(n, _)| n.eq_ignore_ascii_case("content-length"))
+                    .map_or(0, |(_, v)| v.parse::<usize>().unwrap());
+                let mut body_bytes = vec![0; len];
+                reader.read_exact(&mut body_bytes).unwrap();
+
+                recorded.lock().unwrap().push(Request {
+                    method,
+                    path,
+                    headers,
+                    body: String::from_utf8(body_bytes).unwrap(),
+                });
+
+                let response = format!(
+                    "HTTP/1.1 {} X\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        Server { url, requests }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_url() {
+        assert_eq!(
+            Url::parse("http://localhost:8080/aoc").unwrap(),
+            Url {
+                host: "localhost".to_string(),
+                port: 8080,
+                path: "/aoc".to_string(),
+            }
+        );
+        assert_eq!(Url::parse("http://example.com").unwrap().path, "/");
+        assert_eq!(Url::parse("http://example.com").unwrap().port, 80);
+        assert!(matches!(
+            Url::parse("https://example.com"),
+            Err(HttpError::TlsUnsupported(_))
+        ));
+        assert!(matches!(
+            Url::parse("ftp://example.com"),
+            Err(HttpError::InvalidUrl(_))
+        ));
+        assert!(Url::parse("http://:80/").is_err());
+        assert!(Url::parse("http://host:port/").is_err());
+    }
+
+    #[test]
+    fn test_read_response() {
+        let raw = "HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello, ignored";
+        let response = read_response(raw.as_bytes()).unwrap();
+        assert_eq!(
+            response,
+            Response {
+                status: 200,
+                body: "hello".to_string()
+            }
+        );
+
+        let raw = "HTTP/1.1 404 Not Found\r\nTransfer-Encoding: chunked\r\n\r\n3\r\nabc\r\n2\r\nde\r\n0\r\n\r\n";
+        let response = read_response(raw.as_bytes()).unwrap();
+        assert_eq!((response.status, response.body.as_str()), (404, "abcde"));
+
+        assert!(read_response("garbage".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_get_and_post() {
+        let server = mock::serve(vec![(200, "got"), (201, "posted")]);
+
+        let response = get(&format!("{}/a/b", server.url), &[("Cookie", "x=1")]).unwrap();
+        assert_eq!((response.status, response.body.as_str()), (200, "got"));
+
+        let response = post_form(&format!("{}/c", server.url), &[], "k=v").unwrap();
+        assert_eq!((response.status, response.body.as_str()), (201, "posted"));
+
+        let requests = server.requests.lock().unwrap();
+        assert_eq!(requests[0].method, "GET");
+        assert_eq!(requests[0].path, "/a/b");
+        assert_eq!(requests[0].header("cookie"), Some("x=1"));
+        assert_eq!(requests[1].method, "POST");
+        assert_eq!(requests[1].body, "k=v");
+    }
+}
@@ -0,0 +1,160 @@
+//! puzzle inputs, loaded at runtime.
+//! an input comes from an explicit path, stdin (`-`), or `inputs/dayNN.txt` by convention.
+
+use std::fmt;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+pub const INPUT_DIR: &str = "inputs";
+/// a directory to read and download inputs instead of `inputs/`
+pub const CACHE_DIR_VAR: &str = "AOC_CACHE_DIR";
+
+#[derive(Debug)]
+pub enum InputError {
+    NotFound { day: u32, path: PathBuf },
+    Io { path: PathBuf, error: io::Error },
+}
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::NotFound { day, path } => write!(
+                f,
+                "input for day {} not found at [{}], save it there or pass --input <PATH>",
+                day,
+                path.display()
+            ),
+            InputError::Io { path, error } => {
+                write!(f, "can't read [{}]: {}", path.display(), error)
+            }
+        }
+    }
+}
+impl std::error::Error for InputError {}
+
+/// `dayNN.txt`
+pub fn file_name(day: u32) -> String {
+    format!("day{:02}.txt", day)
+}
+
+/// `inputs/dayNN.txt` relative to `dir`
+pub fn default_path(dir: &Path, day: u32) -> PathBuf {
+    dir.join(INPUT_DIR).join(file_name(day))
+}
+
+/// where inputs are read from and downloaded to: `AOC_CACHE_DIR` if it is set,
+/// else `inputs/` in the current directory if there is one, else `inputs/` in the crate root
+pub fn input_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os(CACHE_DIR_VAR).filter(|dir| !dir.is_empty()) {
+        return PathBuf::from(dir);
+    }
+    let local = Path::new(INPUT_DIR);
+    if local.is_dir() {
+        return local.to_path_buf();
+    }
+    Path::new(env!("CARGO_MANIFEST_DIR")).join(INPUT_DIR)
+}
+
+/// reads `path`, or stdin if it is `-`, or `dayNN.txt` in `input_dir()` without a path
+pub fn load(day: u32, path: Option<&Path>) -> Result<String, InputError> {
+    match path {
+        Some(path) if path == Path::new("-") => {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .map_err(|error| InputError::Io {
+                    path: path.to_path_buf(),
+                    error,
+                })?;
+            Ok(input)
+        }
+        Some(path) => read(day, path),
+        None => read(day, &input_dir().join(file_name(day))),
+    }
+}
+
+fn read(day: u32, path: &Path) -> Result<String, InputError> {
+    std::fs::read_to_string(path).map_err(|error| match error.kind() {
+        io::ErrorKind::NotFound => InputError::NotFound {
+            day,
+            path: path.to_path_buf(),
+        },
+        _ => InputError::Io {
+            path: path.to_path_buf(),
+            error,
+        },
+    })
+}
+
+/// loads the input named by `--input <PATH>` in the process arguments, or the default one.
+/// exits with the error message if it can't be read, for use in the `dayNN` binaries.
+pub fn load_from_args(day: u32) -> String {
+    let args = std::env::args().collect::<Vec<_>>();
+    let path = args
+        .iter()
+        .position(|arg| arg == "--input")
+        .and_then(|idx| args.get(idx + 1))
+        .map(PathBuf::from);
+
+    load(day, path.as_deref()).unwrap_or_else(|e| {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    })
+}
+
+/// the real puzzle input for tests, `None` (and the test should pass) if it isn't there
+#[cfg(test)]
+pub(crate) fn test_input(day: u32) -> Option<String> {
+    let path = default_path(Path::new(env!("CARGO_MANIFEST_DIR")), day);
+    match std::fs::read_to_string(&path) {
+        Ok(input) => Some(input),
+        Err(_) => {
+            eprintln!("skipping: [{}] not found", path.display());
+            None
+        }
+    }
+}
+
+/// an empty directory for the test `name`, not shared with other test runs
+#[cfg(test)]
+pub(crate) fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("aoc-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_default_path() {
+        assert_eq!(
+            default_path(Path::new("x"), 3),
+            Path::new("x").join("inputs").join("day03.txt")
+        );
+    }
+
+    #[test]
+    fn test_load_not_found() {
+        // the same error for an explicit path as for the default one
+        let path = Path::new("no/such/day07.txt");
+        let err = load(7, Some(path)).unwrap_err();
+        assert!(matches!(err, InputError::NotFound { day: 7, .. }));
+        assert_eq!(
+            err.to_string(),
+            "input for day 7 not found at [no/such/day07.txt], save it there or pass --input <PATH>"
+        );
+
+        let err = load(7, Some(Path::new(env!("CARGO_MANIFEST_DIR")))).unwrap_err();
+        assert!(matches!(err, InputError::Io { .. }));
+        assert!(err.to_string().starts_with("can't read ["));
+    }
+
+    #[test]
+    fn test_load() {
+        let path = test_dir("input-load").join("day02.txt");
+        std::fs::write(&path, "Game 1: 1 red").unwrap();
+        assert_eq!(load(2, Some(&path)).unwrap(), "Game 1: 1 red");
+    }
+}
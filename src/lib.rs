@@ -0,0 +1,24 @@
+//! solvers for each day of advent of code 2023.
+//! the `dayNN` binaries are thin wrappers printing the answers for the puzzle input.
+
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+
+pub mod answers;
+pub mod bench;
+pub mod cli;
+pub mod error;
+pub mod examples;
+pub mod html;
+pub mod http;
+pub mod input;
+pub mod matcher;
+pub mod registry;
+pub mod runner;
+pub mod scaffold;
+pub mod site;
+pub mod solution;
+pub mod submit;
@@ -0,0 +1,266 @@
+//! finding the leftmost and rightmost of many patterns in a text, overlaps included.
+//! an aho-corasick automaton over the patterns finds the leftmost in one forward scan,
+//! and one over the reversed patterns finds the rightmost in one backward scan.
+
+/// an occurrence of `patterns[pattern]` at the bytes `start..end`
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Match {
+    pub pattern: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// a trie of patterns with failure links
+#[derive(Debug)]
+struct Automaton {
+    /// transitions of each state, sorted by byte
+    goto: Vec<Vec<(u8, usize)>>,
+    /// the state of the longest proper suffix that is also in the trie
+    fail: Vec<usize>,
+    /// the patterns ending in each state, including through failure links
+    outputs: Vec<Vec<usize>>,
+}
+impl Automaton {
+    /// `patterns` are the index of each pattern and its bytes
+    fn new(patterns: impl Iterator<Item = (usize, Vec<u8>)>) -> Self {
+        let mut automaton = Automaton {
+            goto: vec![Vec::new()],
+            fail: vec![0],
+            outputs: vec![Vec::new()],
+        };
+
+        for (pattern, bytes) in patterns {
+            let mut state = 0;
+            for b in bytes {
+                state = match automaton.child(state, b) {
+                    Some(next) => next,
+                    None => {
+                        let next = automaton.goto.len();
+                        automaton.goto.push(Vec::new());
+                        automaton.fail.push(0);
+                        automaton.outputs.push(Vec::new());
+                        let transitions = &mut automaton.goto[state];
+                        let at = transitions.partition_point(|&(c, _)| c < b);
+                        transitions.insert(at, (b, next));
+                        next
+                    }
+                };
+            }
+            automaton.outputs[state].push(pattern);
+        }
+
+        // breadth first, so the failure of a state is known before its children need it
+        let mut queue = std::collections::VecDeque::from([0]);
+        while let Some(state) = queue.pop_front() {
+            for (b, child) in automaton.goto[state].clone() {
+                let fail = if state == 0 {
+                    0
+                } else {
+                    automaton.next(automaton.fail[state], b)
+                };
+                automaton.fail[child] = fail;
+                let inherited = automaton.outputs[fail].clone();
+                automaton.outputs[child].extend(inherited);
+                queue.push_back(child);
+            }
+        }
+
+        automaton
+    }
+
+    fn child(&self, state: usize, b: u8) -> Option<usize> {
+        let transitions = &self.goto[state];
+        transitions
+            .binary_search_by_key(&b, |&(c, _)| c)
+            .ok()
+            .map(|idx| transitions[idx].1)
+    }
+
+    fn next(&self, mut state: usize, b: u8) -> usize {
+        loop {
+            if let Some(next) = self.child(state, b) {
+                return next;
+            }
+            if state == 0 {
+                return 0;
+            }
+            state = self.fail[state];
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Matcher {
+    forward: Automaton,
+    backward: Automaton,
+    lens: Vec<usize>,
+    max_len: usize,
+}
+impl Matcher {
+    /// empty patterns never match
+    pub fn new<P: AsRef<[u8]>>(patterns: &[P]) -> Self {
+        let patterns = patterns.iter().map(|p| p.as_ref()).collect::<Vec<_>>();
+        let non_empty = || patterns.iter().enumerate().filter(|(_, p)| !p.is_empty());
+
+        let lens = patterns.iter().map(|p| p.len()).collect::<Vec<_>>();
+        Matcher {
+            forward: Automaton::new(non_empty().map(|(idx, p)| (idx, p.to_vec()))),
+            backward: Automaton::new(
+                non_empty().map(|(idx, p)| (idx, p.iter().rev().copied().collect())),
+            ),
+            max_len: lens.iter().copied().max().unwrap_or(0),
+            lens,
+        }
+    }
+
+    /// the occurrence starting first, the earliest pattern of those starting there.
+    /// same as the smallest `text.find(pattern)` over the patterns, in one scan.
+    pub fn leftmost(&self, text: &str) -> Option<Match> {
+        let mut best: Option<Match> = None;
+        let mut state = 0;
+        for (idx, &b) in text.as_bytes().iter().enumerate() {
+            // anything ending from here on starts after the best
+            if best.is_some_and(|m| idx - m.start >= self.max_len) {
+                break;
+            }
+
+            state = self.forward.next(state, b);
+            for &pattern in &self.forward.outputs[state] {
+                let start = idx + 1 - self.lens[pattern];
+                if best.is_none_or(|m| (start, pattern) < (m.start, m.pattern)) {
+                    best = Some(Match {
+                        pattern,
+                        start,
+                        end: idx + 1,
+                    });
+                }
+            }
+        }
+        best
+    }
+
+    /// the occurrence starting last, the earliest pattern of those starting there.
+    /// same as the largest `text.rfind(pattern)` over the patterns, in one scan from the end.
+    pub fn rightmost(&self, text: &str) -> Option<Match> {
+        let bytes = text.as_bytes();
+        let mut state = 0;
+        for (idx, &b) in bytes.iter().enumerate().rev() {
+            state = self.backward.next(state, b);
+            // everything found here starts at `idx`, and nothing found later starts after it
+            if let Some(&pattern) = self.backward.outputs[state].iter().min() {
+                return Some(Match {
+                    pattern,
+                    start: idx,
+                    end: idx + self.lens[pattern],
+                });
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const WORDS: &[&str] = &[
+        "1", "2", "3", "4", "5", "6", "7", "8", "9", "one", "two", "three", "four", "five", "six",
+        "seven", "eight", "nine",
+    ];
+
+    /// what the matcher replaces
+    fn naive(patterns: &[&str], text: &str) -> (Option<Match>, Option<Match>) {
+        let found = |find: fn(&str, &str) -> Option<usize>| {
+            patterns
+                .iter()
+                .enumerate()
+                .filter(|(_, p)| !p.is_empty())
+                .filter_map(|(idx, p)| {
+                    find(text, p).map(|start| Match {
+                        pattern: idx,
+                        start,
+                        end: start + p.len(),
+                    })
+                })
+                .collect::<Vec<_>>()
+        };
+        let left = found(|t, p| t.find(p))
+            .into_iter()
+            .min_by_key(|m| (m.start, m.pattern));
+        let right = found(|t, p| t.rfind(p))
+            .into_iter()
+            .min_by_key(|m| (usize::MAX - m.start, m.pattern));
+        (left, right)
+    }
+
+    #[test]
+    fn test_overlapping() {
+        let matcher = Matcher::new(WORDS);
+        let m = |pattern, start, end| {
+            Some(Match {
+                pattern,
+                start,
+                end,
+            })
+        };
+
+        assert_eq!(matcher.leftmost("eightwo"), m(16, 0, 5));
+        assert_eq!(matcher.rightmost("eightwo"), m(10, 4, 7));
+        assert_eq!(matcher.leftmost("threeeighthree"), m(11, 0, 5));
+        assert_eq!(matcher.rightmost("threeeighthree"), m(11, 9, 14));
+        assert_eq!(matcher.rightmost("oneight"), m(16, 2, 7));
+        assert_eq!(matcher.leftmost("xyz"), None);
+        assert_eq!(matcher.rightmost(""), None);
+    }
+
+    #[test]
+    fn test_nested_patterns() {
+        // a longer pattern ending later can still start first
+        let patterns = ["bc", "abcd", "c", ""];
+        let matcher = Matcher::new(&patterns);
+        assert_eq!(matcher.leftmost("xabcd"), naive(&patterns, "xabcd").0);
+        assert_eq!(matcher.leftmost("xabcd").unwrap().pattern, 1);
+        assert_eq!(matcher.rightmost("xabcd"), naive(&patterns, "xabcd").1);
+        assert_eq!(matcher.rightmost("xabcd").unwrap().pattern, 2);
+
+        // same start, the earlier pattern wins like it did with find
+        let patterns = ["ab", "a"];
+        let matcher = Matcher::new(&patterns);
+        assert_eq!(matcher.leftmost("ab").unwrap().pattern, 0);
+        assert_eq!(matcher.rightmost("ab").unwrap().pattern, 0);
+    }
+
+    #[test]
+    fn test_same_as_naive() {
+        let matcher = Matcher::new(WORDS);
+        let alphabet = b"onetwhrfuivsxg1289";
+
+        // a deterministic stream of pseudo random lines
+        let mut seed = 0x2023_u64;
+        for _ in 0..2000 {
+            let len = (seed >> 33) as usize % 24;
+            let line = (0..len)
+                .map(|_| {
+                    seed = seed
+                        .wrapping_mul(6364136223846793005)
+                        .wrapping_add(1442695040888963407);
+                    alphabet[(seed >> 33) as usize % alphabet.len()] as char
+                })
+                .collect::<String>();
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+
+            let (left, right) = naive(WORDS, &line);
+            assert_eq!(matcher.leftmost(&line), left, "[{}]", line);
+            assert_eq!(matcher.rightmost(&line), right, "[{}]", line);
+        }
+    }
+
+    #[test]
+    fn test_unicode() {
+        let patterns = ["é", "ü1"];
+        let matcher = Matcher::new(&patterns);
+        let text = "aéü1é";
+        assert_eq!(matcher.leftmost(text), naive(&patterns, text).0);
+        assert_eq!(matcher.rightmost(text), naive(&patterns, text).1);
+    }
+}
@@ -0,0 +1,63 @@
+//! every solved day, so the runner can dispatch to them by number
+
+use crate::error::Error;
+use crate::solution::{self, Run};
+use crate::{day01, day02, day03, day04, day05};
+
+pub struct Day {
+    pub day: u32,
+    /// parses the input once and solves the given parts, see `solution::run`
+    pub run: fn(&str, &[u32]) -> Result<Run, Error>,
+}
+
+pub const DAYS: &[Day] = &[
+    Day {
+        day: 1,
+        run: solution::run::<day01::Day01>,
+    },
+    Day {
+        day: 2,
+        run: solution::run::<day02::Day02>,
+    },
+    Day {
+        day: 3,
+        run: solution::run::<day03::Day03>,
+    },
+    Day {
+        day: 4,
+        run: solution::run::<day04::Day04>,
+    },
+    Day {
+        day: 5,
+        run: solution::run::<day05::Day05>,
+    },
+];
+
+pub fn find(day: u32) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.day == day)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::solution::Answer;
+
+    #[test]
+    fn test_find() {
+        assert_eq!(find(3).map(|d| d.day), Some(3));
+        assert!(find(0).is_none());
+        assert!(find(26).is_none());
+    }
+
+    #[test]
+    fn test_run() {
+        let day = find(1).unwrap();
+        let run = (day.run)("two1nine", &[1, 2]).unwrap();
+        let answers = run
+            .parts
+            .iter()
+            .map(|p| p.answer.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(answers, vec![Some(Answer::Int(11)), Some(Answer::Int(29))]);
+    }
+}
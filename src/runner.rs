@@ -0,0 +1,356 @@
+//! runs registered days and reports their answers as a table, json or csv
+
+use std::time::Duration;
+
+use crate::answers::{self, Answers, Status};
+use crate::error::Error;
+use crate::registry::Day;
+use crate::solution::Answer;
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub enum Format {
+    #[default]
+    Table,
+    Json,
+    Csv,
+}
+impl Format {
+    pub const ALL: [Format; 3] = [Format::Table, Format::Json, Format::Csv];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Format::Table => "table",
+            Format::Json => "json",
+            Format::Csv => "csv",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Format::ALL.into_iter().find(|f| f.name() == name)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Outcome {
+    pub day: u32,
+    pub part: u32,
+    /// `None` for a part that is not implemented yet, whose status is unknown
+    pub answer: Option<Answer>,
+    /// parsing is shared by both parts of a day
+    pub parse: Duration,
+    pub elapsed: Duration,
+    pub expected: Option<Answer>,
+    pub status: Status,
+}
+
+/// `answers` are the known answers for `input`, `None` if they don't apply to it
+pub fn run(
+    day: &Day,
+    parts: &[u32],
+    input: &str,
+    answers: Option<&Answers>,
+) -> Result<Vec<Outcome>, Error> {
+    let run = (day.run)(input, parts).map_err(|e| e.in_day(day.day))?;
+    Ok(run
+        .parts
+        .into_iter()
+        .map(|p| {
+            let expected = answers.and_then(|a| a.get(day.day, p.part)).cloned();
+            let status = match &p.answer {
+                Some(answer) => answers::check(expected.as_ref(), answer),
+                None => Status::Unknown,
+            };
+            Outcome {
+                day: day.day,
+                part: p.part,
+                answer: p.answer,
+                parse: run.parse,
+                elapsed: p.elapsed,
+                expected,
+                status,
+            }
+        })
+        .collect())
+}
+
+fn format_ms(d: Duration) -> String {
+    format!("{:.3}ms", d.as_secs_f64() * 1000.0)
+}
+
+pub fn format_table(outcomes: &[Outcome]) -> String {
+    let answers = outcomes
+        .iter()
+        .map(|o| o.answer.as_ref().map_or("-".to_string(), |a| a.to_string()))
+        .collect::<Vec<_>>();
+    let expected = outcomes
+        .iter()
+        .map(|o| {
+            o.expected
+                .as_ref()
+                .map_or("-".to_string(), |e| e.to_string())
+        })
+        .collect::<Vec<_>>();
+    let width = |values: &[String], header: &str| {
+        values
+            .iter()
+            .map(|v| v.len())
+            .chain([header.len()])
+            .max()
+            .unwrap()
+    };
+    let (answer_width, expected_width) = (width(&answers, "answer"), width(&expected, "expected"));
+
+    let mut table = format!(
+        "day  part  {:<answer_width$}  {:>10}  {:>10}  {:<expected_width$}  status\n",
+        "answer", "parse", "time", "expected"
+    );
+    for ((o, answer), expected) in outcomes.iter().zip(answers).zip(expected) {
+        table += &format!(
+            "{:>3}  {:>4}  {:<answer_width$}  {:>10}  {:>10}  {:<expected_width$}  {}\n",
+            o.day,
+            o.part,
+            answer,
+            format_ms(o.parse),
+            format_ms(o.elapsed),
+            expected,
+            o.status,
+        );
+    }
+    table
+}
+
+/// a json string literal
+fn json_string(s: &str) -> String {
+    let mut quoted = "\"".to_string();
+    for c in s.chars() {
+        match c {
+            '"' => quoted += "\\\"",
+            '\\' => quoted += "\\\\",
+            '\n' => quoted += "\\n",
+            '\r' => quoted += "\\r",
+            '\t' => quoted += "\\t",
+            c if (c as u32) < 0x20 => quoted += &format!("\\u{:04x}", c as u32),
+            c => quoted.push(c),
+        }
+    }
+    quoted + "\""
+}
+
+/// numbers stay numbers
+fn json_answer(answer: &Answer) -> String {
+    match answer {
+        Answer::Int(n) => n.to_string(),
+        Answer::Str(s) => json_string(s),
+    }
+}
+
+/// an array with an object per outcome, times in nanoseconds
+pub fn format_json(outcomes: &[Outcome]) -> String {
+    let objects = outcomes
+        .iter()
+        .map(|o| {
+            format!(
+                "  {{\"day\": {}, \"part\": {}, \"answer\": {}, \"parse_ns\": {}, \"time_ns\": {}, \"expected\": {}, \"status\": {}}}",
+                o.day,
+                o.part,
+                o.answer.as_ref().map_or("null".to_string(), json_answer),
+                o.parse.as_nanos(),
+                o.elapsed.as_nanos(),
+                o.expected.as_ref().map_or("null".to_string(), json_answer),
+                json_string(&o.status.to_string()),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    if objects.is_empty() {
+        "[]\n".to_string()
+    } else {
+        format!("[\n{}\n]\n", objects.join(",\n"))
+    }
+}
+
+/// quoted only if it has to be
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// a header and a row per outcome, times in nanoseconds
+pub fn format_csv(outcomes: &[Outcome]) -> String {
+    let mut csv = "day,part,answer,parse_ns,time_ns,expected,status\n".to_string();
+    for o in outcomes {
+        csv += &format!(
+            "{},{},{},{},{},{},{}\n",
+            o.day,
+            o.part,
+            o.answer
+                .as_ref()
+                .map_or(String::new(), |a| csv_field(&a.to_string())),
+            o.parse.as_nanos(),
+            o.elapsed.as_nanos(),
+            o.expected
+                .as_ref()
+                .map_or(String::new(), |e| csv_field(&e.to_string())),
+            o.status,
+        );
+    }
+    csv
+}
+
+pub fn format(outcomes: &[Outcome], format: Format) -> String {
+    match format {
+        Format::Table => format_table(outcomes),
+        Format::Json => format_json(outcomes),
+        Format::Csv => format_csv(outcomes),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::registry;
+    use crate::solution::{self, Solution};
+
+    #[test]
+    fn test_run() {
+        let day = registry::find(4).unwrap();
+        let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53";
+        let outcomes = run(day, &[1, 3], input, None).unwrap();
+        assert_eq!(outcomes.len(), 1);
+        assert_eq!((outcomes[0].day, outcomes[0].part), (4, 1));
+        assert_eq!(outcomes[0].answer, Some(Answer::Int(8)));
+        assert_eq!(outcomes[0].status, Status::Unknown);
+
+        let answers = Answers::parse("[day04]\npart1 = 8\npart2 = 2").unwrap();
+        let outcomes = run(day, &[1, 2], input, Some(&answers)).unwrap();
+        let statuses = outcomes.iter().map(|o| o.status).collect::<Vec<_>>();
+        assert_eq!(statuses, vec![Status::Pass, Status::Fail]);
+        assert_eq!(outcomes[1].expected, Some(Answer::Int(2)));
+
+        let err = run(day, &[1], "Card 1: 41 | 8x", None).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "day 4, line 1, column 14: expected a number, found [8x]"
+        );
+    }
+
+    #[test]
+    fn test_run_not_implemented() {
+        // a day fresh from `aoc new`, with part 2 still a stub
+        struct Stub;
+        impl Solution for Stub {
+            type Input = ();
+
+            fn parse(_: &str) -> Result<Self::Input, Error> {
+                Ok(())
+            }
+
+            fn part1(_: &Self::Input) -> Result<Answer, Error> {
+                Ok(1.into())
+            }
+
+            fn part2(_: &Self::Input) -> Result<Answer, Error> {
+                Err(Error::not_implemented("part 2 of day 6"))
+            }
+        }
+        let day = Day {
+            day: 6,
+            run: solution::run::<Stub>,
+        };
+
+        let answers = Answers::parse("[day06]\npart1 = 1\npart2 = 2").unwrap();
+        let outcomes = run(&day, &[1, 2], "", Some(&answers)).unwrap();
+        let statuses = outcomes.iter().map(|o| o.status).collect::<Vec<_>>();
+        assert_eq!(statuses, vec![Status::Pass, Status::Unknown]);
+        assert_eq!(outcomes[1].answer, None);
+        assert!(format_table(&outcomes).contains("  6     2  -     "));
+        assert!(format_csv(&outcomes).contains("\n6,2,,"));
+    }
+
+    #[test]
+    fn test_run_seed_errors() {
+        // no seeds fails both parts, seeds that don't pair up or overflow fail part 2
+        let day = registry::find(5).unwrap();
+        for (input, message) in [
+            (
+                "seeds:\n",
+                "day 5, line 1, column 7: expected a seed, found end of line",
+            ),
+            (
+                "seeds: 1 2 3",
+                "day 5, line 1, column 12: odd number of seeds, expected `<start> <length>` pairs",
+            ),
+            (
+                "seeds: 18446744073709551615 5",
+                "day 5, line 1, column 8: seed range overflows u64",
+            ),
+        ] {
+            let err = run(day, &[1, 2], input, None).unwrap_err();
+            assert_eq!(err.to_string(), message);
+        }
+    }
+
+    fn outcomes() -> [Outcome; 2] {
+        [
+            Outcome {
+                day: 1,
+                part: 1,
+                answer: Some(Answer::Int(53651)),
+                parse: Duration::from_micros(10),
+                elapsed: Duration::from_micros(1500),
+                expected: Some(Answer::Int(53651)),
+                status: Status::Pass,
+            },
+            Outcome {
+                day: 12,
+                part: 2,
+                answer: Some(Answer::Str("abc".to_string())),
+                parse: Duration::from_micros(10),
+                elapsed: Duration::from_micros(20),
+                expected: None,
+                status: Status::Unknown,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_format_table() {
+        let outcomes = outcomes();
+        assert_eq!(
+            format_table(&outcomes),
+            "day  part  answer       parse        time  expected  status
+  1     1  53651      0.010ms     1.500ms  53651     PASS
+ 12     2  abc        0.010ms     0.020ms  -         UNKNOWN
+"
+        );
+    }
+
+    #[test]
+    fn test_format_json() {
+        assert_eq!(
+            format_json(&outcomes()),
+            r#"[
+  {"day": 1, "part": 1, "answer": 53651, "parse_ns": 10000, "time_ns": 1500000, "expected": 53651, "status": "PASS"},
+  {"day": 12, "part": 2, "answer": "abc", "parse_ns": 10000, "time_ns": 20000, "expected": null, "status": "UNKNOWN"}
+]
+"#
+        );
+        assert_eq!(format_json(&[]), "[]\n");
+        assert_eq!(json_string("a\"b\\c\nd\u{1}"), r#""a\"b\\c\nd\u0001""#);
+    }
+
+    #[test]
+    fn test_format_csv() {
+        assert_eq!(
+            format_csv(&outcomes()),
+            "day,part,answer,parse_ns,time_ns,expected,status
+1,1,53651,10000,1500000,53651,PASS
+12,2,abc,10000,20000,,UNKNOWN
+"
+        );
+        assert_eq!(csv_field("a,\"b\""), r#""a,""b""""#);
+    }
+}
@@ -0,0 +1,395 @@
+//! generating the module, binary and registrations of a new day
+
+use std::path::{Path, PathBuf};
+
+const MODULE_TEMPLATE: &str = r##"use crate::error::Error;
+use crate::solution::{Answer, Solution};
+
+pub fn parse_lines(input: &str) -> Vec<String> {
+    input.lines().map(|l| l.to_string()).collect()
+}
+
+pub struct Day{NN};
+impl Solution for Day{NN} {
+    type Input = Vec<String>;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(parse_lines(input))
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, Error> {
+        Ok(part1::solve_lines(input)?.into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, Error> {
+        Ok(part2::solve_lines(input)?.into())
+    }
+}
+
+#[cfg(test)]
+const SAMPLE: &str = {SAMPLE};
+{PARTS}"##;
+
+const PART_TEMPLATE: &str = r##"
+pub mod part{P} {
+    use super::*;
+
+    pub fn solve(input: &str) -> Result<i64, Error> {
+        solve_lines(&parse_lines(input))
+    }
+
+    pub fn solve_lines(lines: &[String]) -> Result<i64, Error> {
+        Err(Error::not_implemented(format!(
+            "part {P} of day {N}, {} lines",
+            lines.len()
+        )))
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        #[test]
+        #[ignore = "not solved yet"]
+        fn test_solve_sample() {
+            assert_eq!(solve(SAMPLE), Ok(0));
+        }
+
+        #[test]
+        #[ignore = "not solved yet"]
+        fn test_solve() {
+            let Some(input) = crate::input::test_input({N}) else {
+                return;
+            };
+            assert_eq!(solve(&input), Ok(0));
+        }
+    }
+}
+"##;
+
+const BIN_TEMPLATE: &str = r##"use advent_of_code_2023::{day{NN}, error, input};
+
+fn main() {
+    let input = &input::load_from_args({N});
+
+    println!("part1: {}", error::or_exit(day{NN}::part1::solve(input)));
+    println!("part2: {}", error::or_exit(day{NN}::part2::solve(input)));
+}
+"##;
+
+fn fill(template: &str, day: u32) -> String {
+    template
+        .replace("{NN}", &format!("{:02}", day))
+        .replace("{N}", &day.to_string())
+}
+
+/// `s` as a raw string literal, with as many `#` as it needs
+fn raw_string(s: &str) -> String {
+    let mut hashes = String::new();
+    while s.contains(&format!("\"{}", hashes)) {
+        hashes.push('#');
+    }
+    format!("r{0}\"{1}\"{0}", hashes, s)
+}
+
+/// `src/dayNN.rs`, with `sample` in the sample tests of both parts
+pub fn module(day: u32, sample: &str) -> String {
+    let parts = [1, 2]
+        .map(|part| fill(PART_TEMPLATE, day).replace("{P}", &part.to_string()))
+        .concat();
+    fill(MODULE_TEMPLATE, day)
+        .replace("{SAMPLE}", &raw_string(sample.trim_end_matches('\n')))
+        .replace("{PARTS}", &parts)
+}
+
+/// `src/bin/dayNN.rs`
+pub fn bin(day: u32) -> String {
+    fill(BIN_TEMPLATE, day)
+}
+
+/// the day of `line` if it starts with `prefix` followed by `dayNN`
+fn day_after(line: &str, prefix: &str) -> Option<u32> {
+    line.strip_prefix(prefix)?
+        .strip_prefix("day")?
+        .get(..2)?
+        .parse()
+        .ok()
+}
+
+/// inserts `text` before the first entry of a later day than `day`, or after the last entry.
+/// `entry_day` gives the day of an entry starting at a line, entries are `len` lines long.
+/// `None` if there are no entries.
+fn insert_ordered(
+    source: &str,
+    day: u32,
+    text: &str,
+    len: usize,
+    entry_day: impl Fn(&[&str], usize) -> Option<u32>,
+) -> Result<Option<String>, String> {
+    let lines = source.split_inclusive('\n').collect::<Vec<_>>();
+    let entries = (0..lines.len())
+        .filter_map(|idx| entry_day(&lines, idx).map(|d| (idx, d)))
+        .collect::<Vec<_>>();
+
+    if entries.iter().any(|&(_, d)| d == day) {
+        return Err(format!("day {} is registered already", day));
+    }
+    let at = match entries.iter().find(|&&(_, d)| d > day) {
+        Some(&(idx, _)) => idx,
+        None => match entries.last() {
+            Some(&(idx, _)) => (idx + len).min(lines.len()),
+            None => return Ok(None),
+        },
+    };
+
+    Ok(Some(
+        [&lines[..at].concat(), text, &lines[at..].concat()].concat(),
+    ))
+}
+
+/// adds `pub mod dayNN;` to `src/lib.rs`
+pub fn register_module(lib: &str, day: u32) -> Result<String, String> {
+    let text = format!("pub mod day{:02};\n", day);
+    insert_ordered(lib, day, &text, 1, |lines, idx| {
+        day_after(lines[idx], "pub mod ")
+    })?
+    .ok_or_else(|| "no `pub mod dayNN;` to follow".to_string())
+}
+
+/// adds a `[[bin]]` for `dayNN` to `Cargo.toml`
+pub fn register_bin(manifest: &str, day: u32) -> Result<String, String> {
+    let text = format!(
+        "[[bin]]\nname = \"day{0:02}\"\npath = \"src/bin/day{0:02}.rs\"\n\n",
+        day
+    );
+    // a table, its name and path, and the blank line after it
+    insert_ordered(manifest, day, &text, 4, |lines, idx| {
+        if lines[idx].trim_end() != "[[bin]]" {
+            return None;
+        }
+        day_after(lines.get(idx + 1)?, "name = \"")
+    })?
+    .ok_or_else(|| "no `[[bin]]` of a day to follow".to_string())
+}
+
+/// adds `dayNN` to the imports and to `DAYS` in `src/registry.rs`
+pub fn register_day(registry: &str, day: u32) -> Result<String, String> {
+    let name = format!("day{:02}", day);
+
+    let (before, rest) = registry
+        .split_once("use crate::{")
+        .ok_or("no `use crate::{...}` in the registry")?;
+    let (imports, after) = rest.split_once("};").ok_or("unclosed `use crate::{`")?;
+    let mut imports = imports
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>();
+    if imports.contains(&name.as_str()) {
+        return Err(format!("day {} is registered already", day));
+    }
+    imports.push(&name);
+    imports.sort();
+
+    // laid out the way rustfmt does
+    let one_line = format!("use crate::{{{}}};", imports.join(", "));
+    let imports = if one_line.len() <= 100 {
+        one_line
+    } else {
+        let mut wrapped = "use crate::{\n".to_string();
+        let mut line = String::new();
+        for import in imports {
+            if !line.is_empty() && 4 + line.len() + import.len() + 1 > 100 {
+                wrapped += &format!("    {}\n", line.trim_end());
+                line.clear();
+            }
+            line += &format!("{}, ", import);
+        }
+        wrapped += &format!("    {}\n}};", line.trim_end());
+        wrapped
+    };
+    let registry = format!("{}{}{}", before, imports, after);
+
+    let entry = format!(
+        "    Day {{\n        day: {},\n        run: solution::run::<{}::Day{:02}>,\n    }},\n",
+        day, name, day
+    );
+    // `Day {`, `day: N,`, `run: ...,` and `},`
+    let entry_day = |lines: &[&str], idx: usize| -> Option<u32> {
+        if lines[idx].trim_end() != "    Day {" {
+            return None;
+        }
+        let day = lines.get(idx + 1)?.trim().strip_prefix("day: ")?;
+        day.strip_suffix(',')?.parse().ok()
+    };
+    if let Some(updated) = insert_ordered(&registry, day, &entry, 4, entry_day)? {
+        return Ok(updated);
+    }
+
+    // the first day goes at the end of the empty `DAYS`
+    let (before, after) = registry
+        .split_once("pub const DAYS")
+        .ok_or("no `DAYS` in the registry")?;
+    let (list, after) = after.split_once("\n];").ok_or("unclosed `DAYS`")?;
+    Ok(format!(
+        "{}pub const DAYS{}\n{}];{}",
+        before, list, entry, after
+    ))
+}
+
+/// creates `src/dayNN.rs` and `src/bin/dayNN.rs` in the crate at `root` and registers them,
+/// returning the files written
+pub fn scaffold(root: &Path, day: u32, sample: &str) -> Result<Vec<PathBuf>, String> {
+    let module_path = root.join("src").join(format!("day{:02}.rs", day));
+    let bin_path = root
+        .join("src")
+        .join("bin")
+        .join(format!("day{:02}.rs", day));
+    for path in [&module_path, &bin_path] {
+        if path.exists() {
+            return Err(format!("[{}] exists already", path.display()));
+        }
+    }
+
+    let read = |path: &Path| {
+        std::fs::read_to_string(path).map_err(|e| format!("can't read [{}]: {}", path.display(), e))
+    };
+    let lib_path = root.join("src").join("lib.rs");
+    let registry_path = root.join("src").join("registry.rs");
+    let manifest_path = root.join("Cargo.toml");
+    // every edit is worked out before anything is written
+    let files = [
+        (module_path, module(day, sample)),
+        (bin_path, bin(day)),
+        (lib_path.clone(), register_module(&read(&lib_path)?, day)?),
+        (
+            registry_path.clone(),
+            register_day(&read(&registry_path)?, day)?,
+        ),
+        (
+            manifest_path.clone(),
+            register_bin(&read(&manifest_path)?, day)?,
+        ),
+    ];
+
+    let mut written = Vec::new();
+    for (path, content) in files {
+        std::fs::write(&path, content)
+            .map_err(|e| format!("can't write [{}]: {}", path.display(), e))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_raw_string() {
+        assert_eq!(raw_string("a\nb"), "r\"a\nb\"");
+        assert_eq!(raw_string("say \"hi\"#"), "r##\"say \"hi\"#\"##");
+    }
+
+    #[test]
+    fn test_module() {
+        let module = module(6, "Time: 7\nDistance: 9\n");
+        assert!(module.contains("pub struct Day06;\nimpl Solution for Day06 {"));
+        assert!(module.contains("const SAMPLE: &str = r\"Time: 7\nDistance: 9\";"));
+        assert!(module.contains("pub mod part1 {"));
+        assert!(module.contains("pub mod part2 {"));
+        assert!(module.contains("crate::input::test_input(6)"));
+        assert_eq!(module.matches("fn test_solve_sample()").count(), 2);
+        assert_eq!(module.matches("fn test_solve()").count(), 2);
+
+        assert!(module.contains("Err(Error::not_implemented("));
+        assert!(!module.contains("todo!"));
+        assert!(bin(6).contains("use advent_of_code_2023::{day06, error, input};"));
+        assert!(bin(6).contains("input::load_from_args(6)"));
+    }
+
+    #[test]
+    fn test_register_module() {
+        let lib = "//! doc\n\npub mod day01;\npub mod day03;\n\npub mod cli;\n";
+        assert_eq!(
+            register_module(lib, 2).unwrap(),
+            "//! doc\n\npub mod day01;\npub mod day02;\npub mod day03;\n\npub mod cli;\n"
+        );
+        assert_eq!(
+            register_module(lib, 4).unwrap(),
+            "//! doc\n\npub mod day01;\npub mod day03;\npub mod day04;\n\npub mod cli;\n"
+        );
+        assert!(register_module(lib, 3).is_err());
+    }
+
+    #[test]
+    fn test_register_bin() {
+        let manifest = "[package]\nname = \"x\"\n\n[[bin]]\nname = \"aoc\"\npath = \"src/bin/aoc.rs\"\n\n\
+            [[bin]]\nname = \"day01\"\npath = \"src/bin/day01.rs\"\n\n# a comment\n\n[dependencies]\n";
+        assert_eq!(
+            register_bin(manifest, 2).unwrap(),
+            "[package]\nname = \"x\"\n\n[[bin]]\nname = \"aoc\"\npath = \"src/bin/aoc.rs\"\n\n\
+            [[bin]]\nname = \"day01\"\npath = \"src/bin/day01.rs\"\n\n\
+            [[bin]]\nname = \"day02\"\npath = \"src/bin/day02.rs\"\n\n# a comment\n\n[dependencies]\n"
+        );
+        let manifest = register_bin(manifest, 3).unwrap();
+        assert_eq!(
+            register_bin(&manifest, 2).unwrap(),
+            register_bin(&register_bin(&manifest, 2).unwrap(), 4)
+                .unwrap()
+                .replace(
+                    "[[bin]]\nname = \"day04\"\npath = \"src/bin/day04.rs\"\n\n",
+                    ""
+                )
+        );
+        assert!(register_bin(&manifest, 1).is_err());
+    }
+
+    #[test]
+    fn test_register_day() {
+        let registry = "use crate::{day01, day03};\n\npub const DAYS: &[Day] = &[\n    Day {\n        day: 1,\n        run: solution::run::<day01::Day01>,\n    },\n    Day {\n        day: 3,\n        run: solution::run::<day03::Day03>,\n    },\n];\n";
+        let updated = register_day(registry, 2).unwrap();
+        assert!(updated.starts_with("use crate::{day01, day02, day03};\n"));
+        let day2 = updated.find("day: 2,").unwrap();
+        assert!(updated.find("day: 1,").unwrap() < day2);
+        assert!(day2 < updated.find("day: 3,").unwrap());
+        assert!(updated.contains("run: solution::run::<day02::Day02>,"));
+
+        let updated = register_day(registry, 9).unwrap();
+        assert!(updated.ends_with(
+            "    Day {\n        day: 9,\n        run: solution::run::<day09::Day09>,\n    },\n];\n"
+        ));
+        assert!(register_day(registry, 3).is_err());
+    }
+
+    #[test]
+    fn test_register_day_wraps_imports() {
+        let mut registry = "use crate::{day01};\n\npub const DAYS: &[Day] = &[\n];\n".to_string();
+        for day in 2..=20 {
+            registry = register_day(&registry, day).unwrap();
+        }
+        assert!(registry.starts_with(
+            "use crate::{\n    day01, day02, day03, day04, day05, day06, day07, day08, day09, day10, day11, day12, day13,\n    day14, day15, day16, day17, day18, day19, day20,\n};\n"
+        ));
+    }
+
+    #[test]
+    fn test_scaffold() {
+        let root = crate::input::test_dir("scaffold");
+        std::fs::create_dir_all(root.join("src").join("bin")).unwrap();
+        for file in ["Cargo.toml", "src/lib.rs", "src/registry.rs"] {
+            let from = Path::new(env!("CARGO_MANIFEST_DIR")).join(file);
+            std::fs::copy(from, root.join(file)).unwrap();
+        }
+
+        let written = scaffold(&root, 24, "sample").unwrap();
+        assert_eq!(written.len(), 5);
+        let read = |file: &str| std::fs::read_to_string(root.join(file)).unwrap();
+        assert!(read("src/day24.rs").contains("r\"sample\""));
+        assert!(read("src/lib.rs").contains("pub mod day24;\n"));
+        assert!(read("src/registry.rs").contains("solution::run::<day24::Day24>"));
+        assert!(read("Cargo.toml").contains("name = \"day24\"\npath = \"src/bin/day24.rs\""));
+
+        assert!(scaffold(&root, 24, "sample").is_err());
+    }
+}
@@ -0,0 +1,326 @@
+//! talking to the puzzle site: downloading inputs into a local cache.
+//! requests carry the session token of a logged in user and are spaced out by a minimum interval,
+//! which is tracked in the cache directory so it holds across runs.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::http::{self, HttpError};
+use crate::input;
+
+pub const BASE_URL: &str = "https://adventofcode.com";
+pub const YEAR: u32 = 2023;
+/// a file holding the session token, looked up like `answers.toml`
+pub const SESSION_FILE: &str = ".aoc-session";
+const LAST_REQUEST_FILE: &str = ".last-request";
+const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+#[derive(Debug)]
+pub enum SiteError {
+    NoSession,
+    /// the session token would go over plain http without `allow_http`
+    InsecureSession(String),
+    Http(HttpError),
+    Status {
+        status: u16,
+        body: String,
+    },
+    Io {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+}
+impl fmt::Display for SiteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SiteError::NoSession => write!(
+                f,
+                "no session token, set AOC_SESSION or save it in {}",
+                SESSION_FILE
+            ),
+            SiteError::InsecureSession(url) => write!(
+                f,
+                "not sending the session token over plain http to [{}], set AOC_ALLOW_HTTP=1 to allow it",
+                url
+            ),
+            SiteError::Http(e) => write!(f, "{}", e),
+            SiteError::Status { status, body } => {
+                let reason = body.lines().next().unwrap_or("").trim();
+                write!(f, "server replied {} [{}]", status, reason)
+            }
+            SiteError::Io { path, error } => write!(f, "[{}]: {}", path.display(), error),
+        }
+    }
+}
+impl std::error::Error for SiteError {}
+impl From<HttpError> for SiteError {
+    fn from(e: HttpError) -> Self {
+        SiteError::Http(e)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    /// only `http://` is supported, point it at a proxy to reach the real site
+    pub base_url: String,
+    pub year: u32,
+    pub session: Option<String>,
+    /// the session token is only sent over plain http when this is set
+    pub allow_http: bool,
+    /// downloaded inputs go to `dayNN.txt` in here
+    pub cache_dir: PathBuf,
+    pub min_interval: Duration,
+}
+impl Config {
+    /// `AOC_BASE_URL`, `AOC_YEAR`, `AOC_SESSION`, `AOC_CACHE_DIR` and `AOC_MIN_INTERVAL` (seconds)
+    /// override the defaults. without `AOC_SESSION` the token is read from `.aoc-session`.
+    /// `AOC_ALLOW_HTTP=1` allows sending it over plain http.
+    pub fn from_env() -> Result<Self, String> {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        let number = |name: &str, default: u64| match var(name) {
+            Some(v) => v
+                .parse::<u64>()
+                .map_err(|_| format!("invalid {} [{}]", name, v)),
+            None => Ok(default),
+        };
+
+        Ok(Config {
+            base_url: var("AOC_BASE_URL").unwrap_or_else(|| BASE_URL.to_string()),
+            year: number("AOC_YEAR", YEAR as u64)? as u32,
+            session: var("AOC_SESSION").or_else(read_session_file),
+            allow_http: var("AOC_ALLOW_HTTP").is_some_and(|v| v == "1"),
+            cache_dir: input::input_dir(),
+            min_interval: Duration::from_secs(number("AOC_MIN_INTERVAL", 5)?),
+        })
+    }
+
+    pub fn url(&self, day: u32, rest: &str) -> String {
+        format!(
+            "{}/{}/day/{}{}",
+            self.base_url.trim_end_matches('/'),
+            self.year,
+            day,
+            rest
+        )
+    }
+
+    /// the url of a request for `day`, checked before anything is throttled or sent
+    pub(crate) fn request_url(&self, day: u32, rest: &str) -> Result<String, SiteError> {
+        let url = self.url(day, rest);
+        http::Url::parse(&url)?;
+        // every url the client supports is plain http, where the token travels in the clear
+        if !self.allow_http {
+            return Err(SiteError::InsecureSession(url));
+        }
+        Ok(url)
+    }
+
+    pub(crate) fn headers(&self) -> Result<Vec<(&str, String)>, SiteError> {
+        let session = self.session.as_deref().ok_or(SiteError::NoSession)?;
+        Ok(vec![
+            ("Cookie", format!("session={}", session)),
+            ("User-Agent", USER_AGENT.to_string()),
+        ])
+    }
+
+    /// where the input of `day` is cached
+    pub fn input_path(&self, day: u32) -> PathBuf {
+        self.cache_dir.join(input::file_name(day))
+    }
+}
+
+/// the first line of `.aoc-session` in the current directory or the crate root
+fn read_session_file() -> Option<String> {
+    [Path::new("."), Path::new(env!("CARGO_MANIFEST_DIR"))]
+        .iter()
+        .find_map(|dir| std::fs::read_to_string(dir.join(SESSION_FILE)).ok())
+        .and_then(|s| s.lines().next().map(|l| l.trim().to_string()))
+        .filter(|s| !s.is_empty())
+}
+
+/// waits until `min_interval` has passed since the last request, then records this one
+pub(crate) fn throttle(config: &Config) -> Result<(), SiteError> {
+    let path = config.cache_dir.join(LAST_REQUEST_FILE);
+    let now = || {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+    };
+
+    let last = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|s| s.trim().parse::<u64>().ok())
+        .map(Duration::from_millis);
+    if let Some(last) = last {
+        if let Some(wait) = (last + config.min_interval).checked_sub(now()) {
+            std::thread::sleep(wait);
+        }
+    }
+
+    let io_error = |error| SiteError::Io {
+        path: path.clone(),
+        error,
+    };
+    std::fs::create_dir_all(&config.cache_dir).map_err(io_error)?;
+    // rounded up, so the next request never comes early
+    let millis = now().as_micros().div_ceil(1000);
+    std::fs::write(&path, millis.to_string()).map_err(io_error)
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Fetched {
+    /// it was already there, nothing was requested
+    Cached(PathBuf),
+    Downloaded(PathBuf),
+}
+
+/// downloads the input of `day` into the cache, unless it is already there
+pub fn fetch_input(config: &Config, day: u32) -> Result<Fetched, SiteError> {
+    let path = config.input_path(day);
+    if path.exists() {
+        return Ok(Fetched::Cached(path));
+    }
+
+    let url = config.request_url(day, "/input")?;
+    let headers = config.headers()?;
+    throttle(config)?;
+    let headers = headers
+        .iter()
+        .map(|(name, value)| (*name, value.as_str()))
+        .collect::<Vec<_>>();
+    let response = http::get(&url, &headers)?;
+    if response.status != 200 {
+        return Err(SiteError::Status {
+            status: response.status,
+            body: response.body,
+        });
+    }
+
+    // written aside and renamed, so an interrupted download never looks cached
+    let partial = path.with_extension("part");
+    std::fs::write(&partial, response.body)
+        .and_then(|_| std::fs::rename(&partial, &path))
+        .map_err(|error| SiteError::Io {
+            path: path.clone(),
+            error,
+        })?;
+    Ok(Fetched::Downloaded(path))
+}
+
+/// a config pointing at `base_url` with a fresh cache directory, for tests
+#[cfg(test)]
+pub(crate) fn test_config(name: &str, base_url: &str) -> Config {
+    let cache_dir = input::test_dir(&format!("site-{}", name));
+    Config {
+        base_url: base_url.to_string(),
+        year: 2023,
+        session: Some("token".to_string()),
+        allow_http: true,
+        cache_dir,
+        min_interval: Duration::ZERO,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Instant;
+
+    use super::*;
+    use crate::http::mock;
+
+    #[test]
+    fn test_fetch_input() {
+        let server = mock::serve(vec![(200, "1abc2\n")]);
+        let config = test_config("fetch", &format!("{}/", server.url));
+
+        let path = config.input_path(1);
+        assert_eq!(
+            fetch_input(&config, 1).unwrap(),
+            Fetched::Downloaded(path.clone())
+        );
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "1abc2\n");
+        // never fetched twice, the server would refuse a second connection anyway
+        assert_eq!(fetch_input(&config, 1).unwrap(), Fetched::Cached(path));
+
+        let requests = server.requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "GET");
+        assert_eq!(requests[0].path, "/2023/day/1/input");
+        assert_eq!(requests[0].header("Cookie"), Some("session=token"));
+        assert!(requests[0].header("User-Agent").is_some());
+    }
+
+    #[test]
+    fn test_fetch_error() {
+        let server = mock::serve(vec![(404, "Please don't repeatedly request this endpoint")]);
+        let config = test_config("fetch-error", &server.url);
+
+        let err = fetch_input(&config, 25).unwrap_err();
+        assert!(matches!(err, SiteError::Status { status: 404, .. }));
+        assert_eq!(
+            err.to_string(),
+            "server replied 404 [Please don't repeatedly request this endpoint]"
+        );
+        assert!(!config.input_path(25).exists());
+
+        let config = Config {
+            session: None,
+            ..config
+        };
+        assert!(matches!(
+            fetch_input(&config, 25),
+            Err(SiteError::NoSession)
+        ));
+
+        let config = test_config("fetch-https", BASE_URL);
+        let err = fetch_input(&config, 25).unwrap_err();
+        assert!(matches!(err, SiteError::Http(HttpError::TlsUnsupported(_))));
+        assert!(err.to_string().contains("needs TLS"));
+        // rejected before throttling, so nothing was recorded
+        assert!(!config.cache_dir.join(LAST_REQUEST_FILE).exists());
+    }
+
+    #[test]
+    fn test_insecure_session() {
+        let server = mock::serve(vec![]);
+        let config = Config {
+            allow_http: false,
+            ..test_config("insecure", &server.url)
+        };
+
+        let err = fetch_input(&config, 1).unwrap_err();
+        assert!(matches!(err, SiteError::InsecureSession(_)));
+        assert!(err.to_string().contains("AOC_ALLOW_HTTP=1"));
+        assert!(server.requests.lock().unwrap().is_empty());
+        assert!(!config.cache_dir.join(LAST_REQUEST_FILE).exists());
+    }
+
+    #[test]
+    fn test_cache_dir() {
+        // inputs are fetched to where they are loaded from
+        let dir = input::test_dir("site-cache-dir");
+        std::fs::write(dir.join("day09.txt"), "cached").unwrap();
+        std::env::set_var(input::CACHE_DIR_VAR, &dir);
+        let (config, loaded) = (Config::from_env(), input::load(9, None));
+        std::env::remove_var(input::CACHE_DIR_VAR);
+
+        assert_eq!(config.unwrap().input_path(9), dir.join("day09.txt"));
+        assert_eq!(loaded.unwrap(), "cached");
+    }
+
+    #[test]
+    fn test_min_interval() {
+        let server = mock::serve(vec![(200, "a"), (200, "b")]);
+        let config = Config {
+            min_interval: Duration::from_millis(300),
+            ..test_config("interval", &server.url)
+        };
+
+        let start = Instant::now();
+        fetch_input(&config, 1).unwrap();
+        fetch_input(&config, 2).unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(300));
+        assert_eq!(std::fs::read_to_string(config.input_path(2)).unwrap(), "b");
+    }
+}
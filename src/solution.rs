@@ -0,0 +1,192 @@
+//! the shape every day shares: parse the input once, then solve both parts from it
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::error::{Error, ErrorKind};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Answer {
+    Int(i64),
+    Str(String),
+}
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{}", n),
+            Answer::Str(s) => write!(f, "{}", s),
+        }
+    }
+}
+impl From<i32> for Answer {
+    fn from(n: i32) -> Self {
+        Answer::Int(n as i64)
+    }
+}
+impl From<i64> for Answer {
+    fn from(n: i64) -> Self {
+        Answer::Int(n)
+    }
+}
+/// numbers beyond i64 are kept as their decimal string
+impl From<u64> for Answer {
+    fn from(n: u64) -> Self {
+        match i64::try_from(n) {
+            Ok(n) => Answer::Int(n),
+            Err(_) => Answer::Str(n.to_string()),
+        }
+    }
+}
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Str(s)
+    }
+}
+
+pub trait Solution {
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input, Error>;
+    /// fails only for inputs that are well formed but can't be solved
+    fn part1(input: &Self::Input) -> Result<Answer, Error>;
+    fn part2(input: &Self::Input) -> Result<Answer, Error>;
+}
+
+/// answers and timings of running some parts of a solution
+#[derive(Debug)]
+pub struct Run {
+    pub parse: Duration,
+    pub parts: Vec<PartRun>,
+}
+
+#[derive(Debug)]
+pub struct PartRun {
+    pub part: u32,
+    /// `None` for a part that is not implemented yet
+    pub answer: Option<Answer>,
+    pub elapsed: Duration,
+}
+
+/// parses `input` once and solves each of `parts` from it, skipping parts other than 1 and 2.
+/// a part failing with `ErrorKind::NotImplemented` has no answer rather than failing the run.
+pub fn run<S: Solution>(input: &str, parts: &[u32]) -> Result<Run, Error> {
+    let start = Instant::now();
+    let parsed = S::parse(input)?;
+    let parse = start.elapsed();
+
+    let parts = parts
+        .iter()
+        .filter_map(|&part| {
+            let solve: fn(&S::Input) -> Result<Answer, Error> = match part {
+                1 => S::part1,
+                2 => S::part2,
+                _ => return None,
+            };
+
+            let start = Instant::now();
+            let answer = match solve(&parsed) {
+                Ok(answer) => Some(answer),
+                Err(e) if matches!(e.kind, ErrorKind::NotImplemented(_)) => None,
+                Err(e) => return Some(Err(e)),
+            };
+            Some(Ok(PartRun {
+                part,
+                answer,
+                elapsed: start.elapsed(),
+            }))
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(Run { parse, parts })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Words;
+    impl Solution for Words {
+        type Input = Vec<String>;
+
+        fn parse(input: &str) -> Result<Self::Input, Error> {
+            let words = input
+                .split_whitespace()
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>();
+            match words.iter().find(|w| w.contains('!')) {
+                Some(w) => {
+                    let at = input.find(w.as_str()).unwrap();
+                    Err(Error::unexpected(input, &input[at..at + w.len()], "a word"))
+                }
+                None => Ok(words),
+            }
+        }
+
+        fn part1(input: &Self::Input) -> Result<Answer, Error> {
+            Ok((input.len() as i32).into())
+        }
+
+        fn part2(input: &Self::Input) -> Result<Answer, Error> {
+            Ok(input.concat().into())
+        }
+    }
+
+    /// a scaffolded day with only part 1 solved
+    struct Stub;
+    impl Solution for Stub {
+        type Input = ();
+
+        fn parse(_: &str) -> Result<Self::Input, Error> {
+            Ok(())
+        }
+
+        fn part1(_: &Self::Input) -> Result<Answer, Error> {
+            Ok(1.into())
+        }
+
+        fn part2(_: &Self::Input) -> Result<Answer, Error> {
+            Err(Error::not_implemented("part 2"))
+        }
+    }
+
+    #[test]
+    fn test_run() {
+        let run = run::<Words>("a bc d", &[2, 3, 1]).unwrap();
+        let answers = run
+            .parts
+            .iter()
+            .map(|p| (p.part, p.answer.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            answers,
+            vec![
+                (2, Some(Answer::Str("abcd".to_string()))),
+                (1, Some(Answer::Int(3)))
+            ]
+        );
+
+        let run = super::run::<Stub>("", &[1, 2]).unwrap();
+        let answers = run
+            .parts
+            .iter()
+            .map(|p| p.answer.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(answers, vec![Some(Answer::Int(1)), None]);
+
+        let err = super::run::<Words>("a b! c", &[1]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 3: expected a word, found [b!]"
+        );
+    }
+
+    #[test]
+    fn test_answer() {
+        assert_eq!(Answer::from(42_u64), Answer::Int(42));
+        assert_eq!(
+            Answer::from(u64::MAX),
+            Answer::Str("18446744073709551615".to_string())
+        );
+        assert_eq!(Answer::from(-7).to_string(), "-7");
+    }
+}
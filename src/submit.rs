@@ -0,0 +1,520 @@
+//! submitting answers to the puzzle site.
+//! every attempt goes to a log in the cache directory, which is consulted before posting:
+//! a wrong answer is never sent twice, a solved part is not sent again,
+//! and nothing is sent while the site asked us to wait.
+
+use std::fmt;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::http;
+use crate::site::{self, Config, SiteError};
+use crate::solution::Answer;
+
+pub const ATTEMPTS_FILE: &str = "attempts.log";
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// wrong, without a hint
+    Incorrect,
+    /// the answer was not checked, as another one was given too recently
+    Wait,
+    /// the part was solved already, the answer was not checked
+    AlreadySolved,
+}
+impl Verdict {
+    const ALL: [Verdict; 6] = [
+        Verdict::Correct,
+        Verdict::TooHigh,
+        Verdict::TooLow,
+        Verdict::Incorrect,
+        Verdict::Wait,
+        Verdict::AlreadySolved,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Verdict::Correct => "correct",
+            Verdict::TooHigh => "too-high",
+            Verdict::TooLow => "too-low",
+            Verdict::Incorrect => "incorrect",
+            Verdict::Wait => "wait",
+            Verdict::AlreadySolved => "already-solved",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Verdict::ALL.into_iter().find(|v| v.name() == name)
+    }
+
+    pub fn is_wrong(&self) -> bool {
+        matches!(
+            self,
+            Verdict::TooHigh | Verdict::TooLow | Verdict::Incorrect
+        )
+    }
+}
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// what the site said about an answer
+#[derive(Debug, PartialEq)]
+pub struct Reply {
+    pub verdict: Verdict,
+    /// how long to wait before the next answer
+    pub cooldown: Duration,
+    /// the text of the reply, without markup
+    pub message: String,
+}
+
+/// the text of the `<article>` in `html`, or of the whole page if there is none
+fn text(html: &str) -> String {
+    let article = html
+        .split_once("<article")
+        .and_then(|(_, rest)| rest.split_once("</article>"))
+        .map_or(html, |(article, _)| article);
+    let article = article.split_once('>').map_or(article, |(_, rest)| rest);
+
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// "please wait 5 minutes before trying again", "You have 1m 5s left to wait"
+fn parse_cooldown(text: &str) -> Option<Duration> {
+    let text = text.to_lowercase();
+    if let Some((before, _)) = text.split_once(" left to wait") {
+        let (_, left) = before.rsplit_once("you have ")?;
+        let mut secs = 0;
+        for token in left.split_whitespace() {
+            let (n, unit) = token.split_at(token.find(|c: char| !c.is_ascii_digit())?);
+            let n = n.parse::<u64>().ok()?;
+            secs += match unit {
+                "h" => n * 3600,
+                "m" => n * 60,
+                "s" => n,
+                _ => return None,
+            };
+        }
+        return Some(Duration::from_secs(secs));
+    }
+
+    let (_, rest) = text.split_once("please wait ")?;
+    let mut words = rest.split_whitespace();
+    let n = match words.next()? {
+        "one" | "a" | "an" => 1,
+        n => n.parse::<u64>().ok()?,
+    };
+    let unit = match words.next()?.trim_end_matches('s') {
+        "hour" => 3600,
+        "minute" => 60,
+        "second" => 1,
+        _ => return None,
+    };
+    Some(Duration::from_secs(n * unit))
+}
+
+pub fn parse_reply(html: &str) -> Option<Reply> {
+    let message = text(html);
+    let verdict = if message.contains("That's the right answer") {
+        Verdict::Correct
+    } else if message.contains("That's not the right answer") {
+        if message.contains("too high") {
+            Verdict::TooHigh
+        } else if message.contains("too low") {
+            Verdict::TooLow
+        } else {
+            Verdict::Incorrect
+        }
+    } else if message.contains("You gave an answer too recently") {
+        Verdict::Wait
+    } else if message.contains("You don't seem to be solving the right level") {
+        Verdict::AlreadySolved
+    } else {
+        return None;
+    };
+
+    Some(Reply {
+        verdict,
+        cooldown: parse_cooldown(&message).unwrap_or_default(),
+        message,
+    })
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Attempt {
+    /// seconds since the unix epoch
+    pub at: u64,
+    pub year: u32,
+    pub day: u32,
+    pub part: u32,
+    pub verdict: Verdict,
+    pub cooldown: Duration,
+    pub answer: String,
+}
+impl Attempt {
+    /// `<at> <year> <day> <part> <verdict> <cooldown secs> <answer>`, the answer may contain spaces
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.splitn(7, ' ');
+        Some(Attempt {
+            at: fields.next()?.parse().ok()?,
+            year: fields.next()?.parse().ok()?,
+            day: fields.next()?.parse().ok()?,
+            part: fields.next()?.parse().ok()?,
+            verdict: Verdict::from_name(fields.next()?)?,
+            cooldown: Duration::from_secs(fields.next()?.parse().ok()?),
+            answer: fields.next()?.to_string(),
+        })
+    }
+}
+impl fmt::Display for Attempt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {} {} {}",
+            self.at,
+            self.year,
+            self.day,
+            self.part,
+            self.verdict,
+            self.cooldown.as_secs(),
+            self.answer
+        )
+    }
+}
+
+#[derive(Debug)]
+pub enum SubmitError {
+    Site(SiteError),
+    /// the part was solved with this answer
+    Solved(String),
+    /// this answer was given before and was wrong
+    Repeated(Verdict),
+    /// the site asked to wait this much longer
+    Cooldown(Duration),
+    UnexpectedReply(String),
+    InvalidLog(usize),
+}
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubmitError::Site(e) => write!(f, "{}", e),
+            SubmitError::Solved(answer) => write!(f, "already solved with [{}]", answer),
+            SubmitError::Repeated(verdict) => {
+                write!(f, "this answer was submitted before: {}", verdict)
+            }
+            SubmitError::Cooldown(left) => write!(
+                f,
+                "the site asked to wait, try again in {}s",
+                left.as_secs().max(1)
+            ),
+            SubmitError::UnexpectedReply(text) => write!(f, "unexpected reply [{}]", text),
+            SubmitError::InvalidLog(line) => {
+                write!(f, "{} line {}: invalid attempt", ATTEMPTS_FILE, line)
+            }
+        }
+    }
+}
+impl std::error::Error for SubmitError {}
+impl From<SiteError> for SubmitError {
+    fn from(e: SiteError) -> Self {
+        SubmitError::Site(e)
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn log_path(config: &Config) -> PathBuf {
+    config.cache_dir.join(ATTEMPTS_FILE)
+}
+
+/// every attempt so far, oldest first
+pub fn attempts(config: &Config) -> Result<Vec<Attempt>, SubmitError> {
+    let path = log_path(config);
+    let log = match std::fs::read_to_string(&path) {
+        Ok(log) => log,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(SiteError::Io { path, error }.into()),
+    };
+
+    log.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| Attempt::parse(line).ok_or(SubmitError::InvalidLog(idx + 1)))
+        .collect()
+}
+
+fn record(config: &Config, attempt: &Attempt) -> Result<(), SubmitError> {
+    use std::io::Write;
+
+    let path = log_path(config);
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut log| writeln!(log, "{}", attempt))
+        .map_err(|error| SiteError::Io { path, error }.into())
+}
+
+/// the reason not to submit `answer`, judging by earlier attempts at the puzzles of `year`
+fn check_attempts(
+    attempts: &[Attempt],
+    year: u32,
+    day: u32,
+    part: u32,
+    answer: &str,
+    now: u64,
+) -> Result<(), SubmitError> {
+    let attempts = attempts
+        .iter()
+        .filter(|a| a.year == year)
+        .collect::<Vec<_>>();
+    let same_part = || attempts.iter().filter(|a| a.day == day && a.part == part);
+
+    if let Some(solved) = same_part().find(|a| a.verdict == Verdict::Correct) {
+        return Err(SubmitError::Solved(solved.answer.clone()));
+    }
+    if let Some(tried) = same_part().find(|a| a.verdict.is_wrong() && a.answer == answer) {
+        return Err(SubmitError::Repeated(tried.verdict));
+    }
+    // the site doesn't tell which puzzle of the year a wait is for, so any of them blocks
+    if let Some(until) = attempts.iter().map(|a| a.at + a.cooldown.as_secs()).max() {
+        if until > now {
+            return Err(SubmitError::Cooldown(Duration::from_secs(until - now)));
+        }
+    }
+    Ok(())
+}
+
+/// `application/x-www-form-urlencoded`
+fn form_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// posts `answer` for a part, unless earlier attempts tell it is pointless,
+/// and records the reply
+pub fn submit(config: &Config, day: u32, part: u32, answer: &Answer) -> Result<Reply, SubmitError> {
+    let answer = answer.to_string();
+    check_attempts(&attempts(config)?, config.year, day, part, &answer, now())?;
+
+    let url = config.request_url(day, "/answer")?;
+    let headers = config.headers()?;
+    site::throttle(config)?;
+    let headers = headers
+        .iter()
+        .map(|(name, value)| (*name, value.as_str()))
+        .collect::<Vec<_>>();
+    let body = format!("level={}&answer={}", part, form_encode(&answer));
+    let response = http::post_form(&url, &headers, &body).map_err(SiteError::from)?;
+    if response.status != 200 {
+        return Err(SiteError::Status {
+            status: response.status,
+            body: response.body,
+        }
+        .into());
+    }
+
+    let reply = parse_reply(&response.body)
+        .ok_or_else(|| SubmitError::UnexpectedReply(text(&response.body)))?;
+    record(
+        config,
+        &Attempt {
+            at: now(),
+            year: config.year,
+            day,
+            part,
+            verdict: reply.verdict,
+            cooldown: reply.cooldown,
+            answer,
+        },
+    )?;
+    Ok(reply)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::http::mock;
+
+    const TOO_HIGH: &str = "<html><body><main><article><p>That's not the right answer; \
+        your answer is too high.  If you're stuck, make sure you're using the full input data. \
+        Please wait one minute before trying again. \
+        [<a href=\"/2023/day/5\">Return to Day 5</a>]</p></article></main></body></html>";
+    const CORRECT: &str =
+        "<article><p>That's the right answer!  You are <span class=\"day-success\">\
+        one gold star</span> closer to restoring snow operations.</p></article>";
+    const TOO_RECENT: &str = "<article><p>You gave an answer too recently; you have to wait \
+        after submitting an answer before trying again.  You have 1m 5s left to wait. \
+        [<a href=\"/2023/day/5\">Return to Day 5</a>]</p></article>";
+
+    #[test]
+    fn test_parse_reply() {
+        let reply = parse_reply(TOO_HIGH).unwrap();
+        assert_eq!(reply.verdict, Verdict::TooHigh);
+        assert_eq!(reply.cooldown, Duration::from_secs(60));
+        assert!(reply
+            .message
+            .starts_with("That's not the right answer; your answer"));
+        assert!(reply.message.ends_with("[Return to Day 5]"));
+
+        let reply = parse_reply(CORRECT).unwrap();
+        assert_eq!(
+            (reply.verdict, reply.cooldown),
+            (Verdict::Correct, Duration::ZERO)
+        );
+
+        let reply = parse_reply(TOO_RECENT).unwrap();
+        assert_eq!(reply.verdict, Verdict::Wait);
+        assert_eq!(reply.cooldown, Duration::from_secs(65));
+
+        let reply = parse_reply(
+            "<article><p>That's not the right answer; your answer is too low. \
+            Because you have guessed incorrectly 5 times on this puzzle, \
+            please wait 5 minutes before trying again.</p></article>",
+        )
+        .unwrap();
+        assert_eq!(reply.verdict, Verdict::TooLow);
+        assert_eq!(reply.cooldown, Duration::from_secs(300));
+
+        let reply = parse_reply(
+            "<article><p>You don't seem to be solving the right level.  \
+            Did you already complete it?</p></article>",
+        )
+        .unwrap();
+        assert_eq!(reply.verdict, Verdict::AlreadySolved);
+        assert_eq!(parse_reply("<p>Internal error</p>"), None);
+    }
+
+    #[test]
+    fn test_attempt() {
+        let attempt = Attempt {
+            at: 1701750000,
+            year: 2023,
+            day: 5,
+            part: 2,
+            verdict: Verdict::TooLow,
+            cooldown: Duration::from_secs(60),
+            answer: "two words".to_string(),
+        };
+        assert_eq!(
+            attempt.to_string(),
+            "1701750000 2023 5 2 too-low 60 two words"
+        );
+        assert_eq!(Attempt::parse(&attempt.to_string()), Some(attempt));
+        assert_eq!(Attempt::parse("1701750000 2023 5 2 maybe 60 x"), None);
+    }
+
+    #[test]
+    fn test_check_attempts() {
+        let attempt = |part, verdict, cooldown, answer: &str| Attempt {
+            at: 1000,
+            year: 2023,
+            day: 5,
+            part,
+            verdict,
+            cooldown: Duration::from_secs(cooldown),
+            answer: answer.to_string(),
+        };
+        let attempts = [
+            attempt(1, Verdict::TooHigh, 0, "40"),
+            attempt(1, Verdict::Correct, 0, "35"),
+            attempt(2, Verdict::TooLow, 60, "40"),
+        ];
+
+        assert!(matches!(
+            check_attempts(&attempts, 2023, 5, 1, "35", 2000),
+            Err(SubmitError::Solved(a)) if a == "35"
+        ));
+        assert!(matches!(
+            check_attempts(&attempts, 2023, 5, 2, "40", 2000),
+            Err(SubmitError::Repeated(Verdict::TooLow))
+        ));
+        assert!(matches!(
+            check_attempts(&attempts, 2023, 5, 2, "46", 1030),
+            Err(SubmitError::Cooldown(left)) if left == Duration::from_secs(30)
+        ));
+        assert!(check_attempts(&attempts, 2023, 5, 2, "46", 1060).is_ok());
+        assert!(check_attempts(&attempts, 2023, 4, 1, "40", 1060).is_ok());
+
+        // the same puzzle of another year is neither solved nor waiting
+        assert!(check_attempts(&attempts, 2022, 5, 1, "35", 1030).is_ok());
+        assert!(check_attempts(&attempts, 2022, 5, 2, "40", 1030).is_ok());
+    }
+
+    #[test]
+    fn test_form_encode() {
+        assert_eq!(form_encode("12345"), "12345");
+        assert_eq!(form_encode("a b&c=d"), "a%20b%26c%3Dd");
+    }
+
+    #[test]
+    fn test_submit() {
+        let server = mock::serve(vec![(200, TOO_HIGH), (200, CORRECT)]);
+        let config = site::test_config("submit", &server.url);
+
+        let reply = submit(&config, 5, 1, &Answer::Int(40)).unwrap();
+        assert_eq!(reply.verdict, Verdict::TooHigh);
+        // the same wrong answer and anything during the cooldown never reach the server
+        assert!(matches!(
+            submit(&config, 5, 1, &Answer::Int(40)),
+            Err(SubmitError::Repeated(Verdict::TooHigh))
+        ));
+        assert!(matches!(
+            submit(&config, 5, 1, &Answer::Int(35)),
+            Err(SubmitError::Cooldown(_))
+        ));
+
+        // pretend the minute has passed
+        let mut attempts = attempts(&config).unwrap();
+        attempts[0].at -= 60;
+        std::fs::write(log_path(&config), format!("{}\n", attempts[0])).unwrap();
+
+        let reply = submit(&config, 5, 1, &Answer::Int(35)).unwrap();
+        assert_eq!(reply.verdict, Verdict::Correct);
+        assert!(matches!(
+            submit(&config, 5, 1, &Answer::Int(35)),
+            Err(SubmitError::Solved(_))
+        ));
+
+        let requests = server.requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/2023/day/5/answer");
+        assert_eq!(requests[0].body, "level=1&answer=40");
+        assert_eq!(requests[1].body, "level=1&answer=35");
+        assert_eq!(requests[1].header("Cookie"), Some("session=token"));
+
+        let verdicts = super::attempts(&config)
+            .unwrap()
+            .iter()
+            .map(|a| a.verdict)
+            .collect::<Vec<_>>();
+        assert_eq!(verdicts, vec![Verdict::TooHigh, Verdict::Correct]);
+    }
+}
@@ -0,0 +1,42 @@
+//! runs every example listed in `examples/expected.txt` through the registered solution of its day.
+//! days that are not registered, or only scaffolded, are skipped.
+
+use advent_of_code_2023::examples::{self, Expectation};
+use advent_of_code_2023::registry;
+
+/// the answer of `expectation`, `None` if its day can't solve it yet
+fn solve(expectation: &Expectation) -> Option<String> {
+    let day = registry::find(expectation.day()?)?;
+    let path = examples::default_dir().join(&expectation.file);
+    let input = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("can't read [{}]: {}", path.display(), e));
+
+    let part = expectation.part;
+    match (day.run)(&input, &[part]) {
+        // a scaffolded part has no answer
+        Ok(mut run) => run.parts.pop()?.answer.map(|a| a.to_string()),
+        Err(e) => panic!("{}: {}", expectation.file, e.diagnostic()),
+    }
+}
+
+#[test]
+fn test_examples() {
+    let expectations = examples::load_expectations(&examples::default_dir()).unwrap();
+
+    let mut failed = Vec::new();
+    for expectation in &expectations {
+        match solve(expectation) {
+            Some(answer) if answer == expectation.answer => {}
+            Some(answer) => failed.push(format!(
+                "{} part {}: expected {}, got {}",
+                expectation.file, expectation.part, expectation.answer, answer
+            )),
+            None => eprintln!(
+                "skipping: {} part {} is not solved yet",
+                expectation.file, expectation.part
+            ),
+        }
+    }
+
+    assert!(failed.is_empty(), "{}", failed.join("\n"));
+}